@@ -1,14 +1,31 @@
 //! Contains the [Bookmark] model and its associated tests.
 use askama::Template;
+use indexmap::IndexMap;
 use kuchiki::NodeRef;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::node_ref_ext::*;
+use crate::url::Rules;
+use crate::Timestamp;
+
+/// The attributes of the `<A/>` tag that are parsed into the fields of a [Bookmark].
+const KNOWN_ATTRIBUTES: &[&str] = &[
+    "HREF",
+    "ADD_DATE",
+    "LAST_VISIT",
+    "LAST_MODIFIED",
+    "ICON_URI",
+    "ICON",
+    "SHORTCUTURL",
+    "POST_DATA",
+    "TAGS",
+];
 
 /// Implements the specification of the `shortcut` item.
-#[derive(Serialize, Builder, Clone, Debug, Default, Template)]
+#[derive(Serialize, Deserialize, Builder, Clone, Debug, Default, Template)]
 #[builder(setter(into))]
-#[template(path = "bookmark.j2", escape = "none")]
+#[serde(deny_unknown_fields)]
+#[template(path = "bookmark.j2", escape = "netscape")]
 pub struct Bookmark {
     /// The `href` attribute stores the url to the page of the shortcut.
     pub href: String,
@@ -16,17 +33,17 @@ pub struct Bookmark {
     /// The `title` attribute stores the shortcut's title, it is the `<A/>` content.
     pub title: String,
 
-    /// The `add_date` attribute is the date when the item was created (in UNIX time).
+    /// The `add_date` attribute is the date when the item was created, see [Timestamp].
     #[builder(default)]
-    pub add_date: String,
+    pub add_date: Timestamp,
 
-    /// The `last_visit` attribute is the date of the last visit to the site of the shortcut (in UNIX time).
+    /// The `last_visit` attribute is the date of the last visit to the site of the shortcut, see [Timestamp].
     #[builder(default)]
-    pub last_visit: String,
+    pub last_visit: Timestamp,
 
-    /// The `last_modified` attribute is the date of the last modification of the item (in UNIX time).
+    /// The `last_modified` attribute is the date of the last modification of the item, see [Timestamp].
     #[builder(default)]
-    pub last_modified: String,
+    pub last_modified: Timestamp,
 
     /// The `icon_uri` attribute represents the url of the `favicon.ico` of the domain.
     #[builder(default)]
@@ -36,6 +53,41 @@ pub struct Bookmark {
     /// It is usually a png encoded in base64.
     #[builder(default)]
     pub icon: String,
+
+    /// The `description` attribute stores the note of the shortcut, it is the content of the following `<DD/>` tag.
+    ///
+    /// It is plain text, escaped when rendered : an escaped markup like `&lt;b&gt;` round-trips, but the
+    /// tags nested in the `<DD/>` tag are dropped and only their text is kept.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+
+    /// The `tags` attribute stores the labels given to the shortcut, it is the comma-separated `TAGS` attribute.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// The `keyword` attribute stores the keyword typed in the address bar to open the shortcut,
+    /// it is the `SHORTCUTURL` attribute.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<String>,
+
+    /// The `post_data` attribute stores the body sent with the `keyword` searches that use a POST request.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<String>,
+
+    /// The `guid` attribute stores the browser's identifier of the item, it isn't part of the Netscape format.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub guid: String,
+
+    /// The `extra_attributes` map stores the attributes of the `<A/>` tag that aren't modeled,
+    /// like `PRIVATE` or `LAST_CHARSET`. Their names are uppercased and they keep the document's order.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub extra_attributes: IndexMap<String, String>,
 }
 
 impl Bookmark {
@@ -75,8 +127,32 @@ impl Bookmark {
                 builder.icon(attribute.value);
             }
 
+            if let Some(attribute) = node.select_attribute("SHORTCUTURL") {
+                builder.keyword(Some(attribute.value));
+            }
+
+            if let Some(attribute) = node.select_attribute("POST_DATA") {
+                builder.post_data(Some(attribute.value));
+            }
+
+            if let Some(attribute) = node.select_attribute("TAGS") {
+                let tags = attribute
+                    .value
+                    .split(',')
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect::<Vec<_>>();
+
+                builder.tags(tags);
+            }
+
+            builder.extra_attributes(node.select_extra_attributes(KNOWN_ATTRIBUTES));
             builder.title(node.text_contents());
 
+            if let Some(dd) = node.select_description() {
+                builder.description(dd.description_text());
+            }
+
             if let Ok(built) = builder.build() {
                 bookmark = Some(built);
             }
@@ -84,6 +160,12 @@ impl Bookmark {
 
         bookmark
     }
+
+    /// Checks if two bookmarks point to the same page, their `href` are compared once canonicalized
+    /// by the given [Rules].
+    pub fn same_url(&self, other: &Bookmark, rules: &Rules) -> bool {
+        rules.same(&self.href, &other.href)
+    }
 }
 
 impl PartialEq for Bookmark {
@@ -93,6 +175,11 @@ impl PartialEq for Bookmark {
             && self.last_visit == other.last_visit
             && self.last_modified == other.last_modified
             && self.title == other.title
+            && self.description == other.description
+            && self.tags == other.tags
+            && self.keyword == other.keyword
+            && self.post_data == other.post_data
+            && self.extra_attributes == other.extra_attributes
     }
 }
 
@@ -100,12 +187,18 @@ impl PartialEq for Bookmark {
 fn mock_bookmark() -> Bookmark {
     Bookmark {
         href: String::from("url"),
-        add_date: String::from("date"),
-        last_visit: String::from("date"),
-        last_modified: String::from("date"),
+        add_date: Timestamp::from("date"),
+        last_visit: Timestamp::from("date"),
+        last_modified: Timestamp::from("date"),
         title: String::from("name"),
         icon_uri: String::from(""),
         icon: String::from("icon"),
+        description: String::new(),
+        tags: vec![],
+        keyword: None,
+        post_data: None,
+        guid: String::new(),
+        extra_attributes: IndexMap::new(),
     }
 }
 
@@ -115,6 +208,29 @@ fn should_render_bookmark_html() {
     assert_eq!(mock_bookmark().render().unwrap(), rendered);
 }
 
+#[test]
+fn should_escape_bookmark_html() {
+    let bookmark = BookmarkBuilder::default()
+        .href(r#"https://example.com/?a=1&b="2""#)
+        .title("<b>Q&A</b>")
+        .build()
+        .unwrap();
+
+    let rendered = r#"<DT><A HREF="https://example.com/?a=1&amp;b=&quot;2&quot;" ADD_DATE="">&lt;b&gt;Q&amp;A&lt;/b&gt;</A>"#;
+    assert_eq!(bookmark.render().unwrap(), rendered);
+}
+
+#[test]
+fn should_render_bookmark_description() {
+    let mut bookmark = mock_bookmark();
+    bookmark.description = String::from("first line\n<b>second</b> line");
+
+    let rendered = r#"<DT><A HREF="url" ADD_DATE="date" LAST_VISIT="date" LAST_MODIFIED="date" ICON="icon">name</A>
+<DD>first line
+&lt;b&gt;second&lt;/b&gt; line"#;
+    assert_eq!(bookmark.render().unwrap(), rendered);
+}
+
 #[test]
 fn should_parse_netscape_bookmark() {
     use kuchiki::parse_html;
@@ -125,7 +241,57 @@ fn should_parse_netscape_bookmark() {
 LAST_MODIFIED="date" ICON="icon">name</A>"#;
     let a = parse_html().one(item).select_first("A").unwrap();
 
-    assert_eq!(Bookmark::from_node(&a.as_node()).unwrap(), mock_bookmark())
+    assert_eq!(Bookmark::from_node(a.as_node()).unwrap(), mock_bookmark())
+}
+
+#[test]
+fn should_parse_netscape_bookmark_keyword_and_tags() {
+    use kuchiki::parse_html;
+    use kuchiki::traits::TendrilSink;
+
+    let item = r#"<DT><A HREF="https://duckduckgo.com/" SHORTCUTURL="ddg" POST_DATA="q=%s" TAGS="search, privacy,">DuckDuckGo</A>"#;
+    let a = parse_html().one(item).select_first("A").unwrap();
+    let bookmark = Bookmark::from_node(a.as_node()).unwrap();
+
+    assert_eq!(bookmark.keyword, Some(String::from("ddg")));
+    assert_eq!(bookmark.post_data, Some(String::from("q=%s")));
+    assert_eq!(bookmark.tags, vec!["search", "privacy"]);
+
+    let rendered = r#"<DT><A HREF="https://duckduckgo.com/" ADD_DATE="" SHORTCUTURL="ddg" POST_DATA="q=%s" TAGS="search,privacy">DuckDuckGo</A>"#;
+    assert_eq!(bookmark.render().unwrap(), rendered);
+}
+
+#[test]
+fn should_roundtrip_bookmark_extra_attributes() {
+    use crate::parser::parse_html;
+    use kuchiki::traits::TendrilSink;
+
+    let item = r#"<DT><A HREF="url" ADD_DATE="date" PRIVATE="0" TOREAD="1" LAST_CHARSET="UTF-8" ID="rdf:#$wGs9N1">name</A>"#;
+    let document = parse_html().one(item);
+    let a = document.select_first("A").unwrap();
+    let bookmark = Bookmark::from_node(a.as_node()).unwrap();
+
+    assert_eq!(
+        bookmark.extra_attributes.keys().collect::<Vec<_>>(),
+        vec!["PRIVATE", "TOREAD", "LAST_CHARSET", "ID"]
+    );
+    assert_eq!(bookmark.render().unwrap(), item);
+
+    let json = serde_json::to_string(&bookmark).unwrap();
+    assert!(json.ends_with(r#""extra_attributes":{"PRIVATE":"0","TOREAD":"1","LAST_CHARSET":"UTF-8","ID":"rdf:#$wGs9N1"}}"#));
+    assert_eq!(serde_json::from_str::<Bookmark>(&json).unwrap(), bookmark);
+}
+
+#[test]
+fn should_serialize_json_bookmark_keyword_and_tags() {
+    let mut bookmark = mock_bookmark();
+    bookmark.tags = vec![String::from("a"), String::from("b")];
+    bookmark.keyword = Some(String::from("kw"));
+
+    let json = r#"{"href":"url","title":"name","add_date":"date","last_visit":"date","last_modified":"date","icon_uri":"","icon":"icon","tags":["a","b"],"keyword":"kw"}"#;
+
+    assert_eq!(serde_json::to_string(&bookmark).unwrap(), json);
+    assert_eq!(serde_json::from_str::<Bookmark>(json).unwrap(), bookmark);
 }
 
 #[test]
@@ -135,3 +301,17 @@ fn should_serialize_json_bookmark() {
 
     assert_eq!(serde_json::to_string(&bookmark).unwrap(), json)
 }
+
+#[test]
+fn should_compare_normalized_urls() {
+    let mut other = mock_bookmark();
+    let bookmark = BookmarkBuilder::default()
+        .href("http://www.example.com/?utm_source=firefox-browser")
+        .title("name")
+        .build()
+        .unwrap();
+    other.href = String::from("https://example.com");
+
+    assert!(bookmark.same_url(&other, &Rules::default()));
+    assert!(!bookmark.same_url(&mock_bookmark(), &Rules::default()));
+}
@@ -0,0 +1,345 @@
+//! Contains the [BookmarkPath] addressing of the items by their titles.
+use std::fmt;
+
+use crate::item::Item;
+use crate::traverse::Iter;
+
+/// A step of a [BookmarkPath], it selects a child by its title.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Segment {
+    /// The `title` attribute stores the title of the selected folder, bookmark, feed or web slice.
+    pub title: String,
+
+    /// The `index` attribute tells apart the siblings with the same title, the first one is `0`.
+    pub index: usize,
+}
+
+impl Segment {
+    /// Creates a segment selecting the `index`-th child titled `title`.
+    pub fn new(title: impl Into<String>, index: usize) -> Self {
+        Segment {
+            title: title.into(),
+            index,
+        }
+    }
+}
+
+/// Addresses an item by the titles of its parent folders and its own title.
+///
+/// A path is written with the titles separated by `/`, like `Bookmarks bar/References/Netscape bookmarks`.
+/// A title is followed by `[n]` to select the `n`-th sibling with this title, counted from `0`.
+/// The `/`, `[` and `\` characters of a title are escaped with a `\`. An empty title is always
+/// followed by its index, like `[0]`. The separators have no title, so they can't be addressed.
+///
+/// ```rust
+/// use bookmarkt::{BookmarkPath, Segment};
+///
+/// let path = BookmarkPath::from("Bookmarks bar/Rust[1]/A\\/B testing");
+///
+/// assert_eq!(path.segments[1], Segment::new("Rust", 1));
+/// assert_eq!(path.segments[2].title, "A/B testing");
+/// assert_eq!(path.to_string(), "Bookmarks bar/Rust[1]/A\\/B testing");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BookmarkPath {
+    /// The `segments` [Vec] stores the steps from the root to the item.
+    pub segments: Vec<Segment>,
+}
+
+impl BookmarkPath {
+    /// Creates an empty path, it addresses the root.
+    pub fn new() -> Self {
+        BookmarkPath::default()
+    }
+
+    /// Checks if the path addresses the root.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Appends a segment selecting the first child with the given title.
+    pub fn push(&mut self, title: impl Into<String>) {
+        self.segments.push(Segment::new(title, 0));
+    }
+
+    /// Creates the path of a child of the addressed folder.
+    pub fn join(&self, segment: Segment) -> Self {
+        let mut joined = self.clone();
+        joined.segments.push(segment);
+        joined
+    }
+
+    /// Gets the path of the parent folder, the root has no parent.
+    pub fn parent(&self) -> Option<Self> {
+        let (_, parent) = self.segments.split_last()?;
+
+        Some(BookmarkPath {
+            segments: parent.to_vec(),
+        })
+    }
+
+    /// Gets the title of the addressed item.
+    pub fn title(&self) -> Option<&str> {
+        self.segments.last().map(|segment| segment.title.as_str())
+    }
+}
+
+impl From<&str> for BookmarkPath {
+    fn from(raw: &str) -> Self {
+        let mut segments = vec![];
+        // the characters of the current segment, with a flag for the escaped ones
+        let mut current: Vec<(char, bool)> = vec![];
+        let mut chars = raw.chars();
+
+        loop {
+            match chars.next() {
+                Some('\\') => current.push((chars.next().unwrap_or('\\'), true)),
+                Some('/') => {
+                    segments.extend(parse_segment(&current));
+                    current.clear();
+                }
+                Some(c) => current.push((c, false)),
+                None => {
+                    segments.extend(parse_segment(&current));
+                    break;
+                }
+            }
+        }
+
+        BookmarkPath { segments }
+    }
+}
+
+/// Parses the characters of a segment, the empty segments are skipped.
+fn parse_segment(chars: &[(char, bool)]) -> Option<Segment> {
+    let text = |chars: &[(char, bool)]| chars.iter().map(|(c, _)| c).collect::<String>();
+
+    if chars.is_empty() {
+        return None;
+    }
+
+    if let (Some(open), Some((']', false))) =
+        (chars.iter().rposition(|&c| c == ('[', false)), chars.last())
+    {
+        let digits = &chars[open + 1..chars.len() - 1];
+
+        if !digits.is_empty() && digits.iter().all(|(c, _)| c.is_ascii_digit()) {
+            if let Ok(index) = text(digits).parse() {
+                return Some(Segment::new(text(&chars[..open]), index));
+            }
+        }
+    }
+
+    Some(Segment::new(text(chars), 0))
+}
+
+impl fmt::Display for BookmarkPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (position, segment) in self.segments.iter().enumerate() {
+            if position > 0 {
+                write!(f, "/")?;
+            }
+
+            for c in segment.title.chars() {
+                if matches!(c, '/' | '[' | '\\') {
+                    write!(f, "\\")?;
+                }
+
+                write!(f, "{}", c)?;
+            }
+
+            // an empty title is written with its index, else the segment would be skipped when parsed
+            if segment.index > 0 || segment.title.is_empty() {
+                write!(f, "[{}]", segment.index)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolves a [BookmarkPath] to the path of indexes of the item.
+pub(crate) fn resolve(children: &[Item], path: &BookmarkPath) -> Option<Vec<usize>> {
+    let mut indexes = vec![];
+    let mut list = children;
+
+    for segment in path.segments.iter() {
+        let (index, item) = list
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.title() == Some(segment.title.as_str()))
+            .nth(segment.index)?;
+
+        indexes.push(index);
+        list = item
+            .take_subfolder()
+            .map(|folder| folder.children.as_slice())
+            .unwrap_or_default();
+    }
+
+    Some(indexes)
+}
+
+/// Builds the [BookmarkPath] of the item at the given path of indexes.
+pub(crate) fn path_of(children: &[Item], indexes: &[usize]) -> Option<BookmarkPath> {
+    let mut path = BookmarkPath::new();
+    let mut list = children;
+
+    for index in indexes {
+        let title = list.get(*index)?.title()?;
+        let siblings = list[..*index]
+            .iter()
+            .filter(|item| item.title() == Some(title))
+            .count();
+
+        path.segments.push(Segment::new(title, siblings));
+        list = list[*index]
+            .take_subfolder()
+            .map(|folder| folder.children.as_slice())
+            .unwrap_or_default();
+    }
+
+    Some(path)
+}
+
+/// Iterates over the items with their [BookmarkPath], depth first and in the document order.
+///
+/// The separators are skipped, since they can't be addressed.
+pub struct Paths<'a> {
+    iter: Iter<'a>,
+}
+
+impl<'a> Paths<'a> {
+    pub(crate) fn new(children: &'a [Item]) -> Self {
+        Paths {
+            iter: Iter::new(children),
+        }
+    }
+}
+
+impl<'a> Iterator for Paths<'a> {
+    type Item = (BookmarkPath, &'a Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let (_, parent, Some(segment), item) = self.iter.next_entry()? {
+                return Some((parent.join(segment), item));
+            }
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn mock_tree() -> Vec<Item> {
+    use crate::bookmark::BookmarkBuilder;
+    use crate::folder::FolderBuilder;
+
+    let bookmark = |name: &str| {
+        Item::Shortcut(
+            BookmarkBuilder::default()
+                .href(name)
+                .title(name)
+                .build()
+                .unwrap(),
+        )
+    };
+
+    let folder = |name: &str, children: Vec<Item>| {
+        Item::Subfolder(
+            FolderBuilder::default()
+                .title(name)
+                .children(children)
+                .build()
+                .unwrap(),
+        )
+    };
+
+    vec![
+        folder("f", vec![bookmark("a")]),
+        Item::Separator,
+        folder("f", vec![bookmark("a"), bookmark("a")]),
+    ]
+}
+
+#[test]
+fn should_parse_and_display_bookmark_path() {
+    let path = BookmarkPath::from("/a\\/b/c[2]/d\\[3]/e[x]/");
+
+    assert_eq!(
+        path.segments,
+        vec![
+            Segment::new("a/b", 0),
+            Segment::new("c", 2),
+            Segment::new("d[3]", 0),
+            Segment::new("e[x]", 0),
+        ]
+    );
+    assert_eq!(path.to_string(), "a\\/b/c[2]/d\\[3]/e\\[x]");
+    assert_eq!(BookmarkPath::from(path.to_string().as_str()), path);
+    assert!(BookmarkPath::from("").is_empty());
+}
+
+#[test]
+fn should_display_empty_titles() {
+    let path = BookmarkPath {
+        segments: vec![
+            Segment::new("a", 0),
+            Segment::new("", 0),
+            Segment::new("", 1),
+        ],
+    };
+
+    assert_eq!(path.to_string(), "a/[0]/[1]");
+    assert_eq!(BookmarkPath::from(path.to_string().as_str()), path);
+    assert_eq!(
+        BookmarkPath::from("a/").segments,
+        vec![Segment::new("a", 0)]
+    );
+
+    let tree = vec![Item::Subfolder(crate::Folder {
+        children: vec![Item::Separator, Item::Shortcut(crate::Bookmark::default())],
+        ..crate::Folder::default()
+    })];
+    let printed = path_of(&tree, &[0, 1]).unwrap().to_string();
+
+    assert_eq!(printed, "[0]/[0]");
+    assert_eq!(
+        resolve(&tree, &BookmarkPath::from(printed.as_str())),
+        Some(vec![0, 1])
+    );
+}
+
+#[test]
+fn should_resolve_bookmark_path() {
+    let tree = mock_tree();
+
+    assert_eq!(
+        resolve(&tree, &BookmarkPath::from("f[1]/a[1]")),
+        Some(vec![2, 1])
+    );
+    assert_eq!(resolve(&tree, &BookmarkPath::from("f/a")), Some(vec![0, 0]));
+    assert_eq!(resolve(&tree, &BookmarkPath::from("f/a[1]")), None);
+    assert_eq!(resolve(&tree, &BookmarkPath::from("f/a/b")), None);
+    assert_eq!(
+        path_of(&tree, &[2, 1]),
+        Some(BookmarkPath::from("f[1]/a[1]"))
+    );
+    assert_eq!(path_of(&tree, &[1]), None);
+}
+
+#[test]
+fn should_iterate_over_paths() {
+    let tree = mock_tree();
+    let paths = Paths::new(&tree)
+        .map(|(path, _)| path.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(paths, vec!["f", "f/a", "f[1]", "f[1]/a", "f[1]/a[1]"]);
+
+    for (path, item) in Paths::new(&tree) {
+        assert_eq!(
+            resolve(&tree, &path).and_then(|i| crate::tree::get(&tree, &i)),
+            Some(item)
+        );
+    }
+}
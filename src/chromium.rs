@@ -0,0 +1,386 @@
+//! Contains the conversions between the [Netscape] model and the Chromium `Bookmarks` profile file.
+//!
+//! The file is used by Chrome, Chromium, Brave and Edge. It holds three special folders :
+//! * the `bookmark_bar` folder, it is mapped to the [Folder] flagged as `personal_toolbar_folder` ;
+//! * the `other` folder, its children are the top-level items of the [Netscape] document, its own
+//!   dates are lost since the document has no dates ;
+//! * the `synced` folder, it is mapped to a regular [Folder] when it isn't empty.
+//!
+//! The dates are stored in microseconds since the WebKit epoch (`1601-01-01`), they are truncated
+//! to seconds like in the HTML exports of Chromium. The file is protected by a MD5 checksum of the
+//! ids, names and urls of its nodes.
+use indexmap::IndexMap;
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+
+use crate::item::Item;
+use crate::Bookmark;
+use crate::Folder;
+use crate::Netscape;
+use crate::Timestamp;
+
+/// The number of seconds between the WebKit epoch and the UNIX epoch.
+const WEBKIT_EPOCH_OFFSET: u64 = 11_644_473_600;
+
+const FOLDER: &str = "folder";
+const URL: &str = "url";
+
+const BOOKMARK_BAR_GUID: &str = "0bc5d13f-2cba-5d74-951f-3f233fe6c908";
+const OTHER_GUID: &str = "82b081ec-3dd3-529c-8475-ab6c344590dd";
+const SYNCED_GUID: &str = "4cf2e351-0e85-532b-bb37-df045d8f8d0f";
+
+/// Represents the whole `Bookmarks` file.
+#[derive(Serialize, Deserialize, Debug)]
+struct Profile {
+    #[serde(default)]
+    checksum: String,
+
+    roots: Roots,
+
+    #[serde(default = "default_version")]
+    version: u32,
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+/// Represents the special folders of the profile.
+#[derive(Serialize, Deserialize, Debug)]
+struct Roots {
+    bookmark_bar: Node,
+
+    other: Node,
+
+    #[serde(default)]
+    synced: Node,
+}
+
+/// Represents a node of the profile, either a `folder` or an `url`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Node {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<Node>>,
+
+    #[serde(default)]
+    date_added: String,
+
+    #[serde(default)]
+    date_last_used: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date_modified: Option<String>,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    guid: String,
+
+    #[serde(default)]
+    id: String,
+
+    #[serde(default)]
+    name: String,
+
+    #[serde(rename = "type", default)]
+    kind: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+/// Converts a WebKit timestamp (in microseconds) to a [Timestamp] in seconds, `0` means no date.
+/// The sub-second part is dropped.
+fn to_timestamp(webkit: &str) -> Timestamp {
+    match webkit.trim().parse::<u64>() {
+        Ok(micros) if micros / 1_000_000 > WEBKIT_EPOCH_OFFSET => {
+            Timestamp::from((micros / 1_000_000 - WEBKIT_EPOCH_OFFSET).to_string())
+        }
+        _ => Timestamp::default(),
+    }
+}
+
+/// Converts a [Timestamp] to a WebKit timestamp (in microseconds), an empty date becomes `0`.
+fn from_timestamp(timestamp: &Timestamp) -> String {
+    timestamp
+        .as_micros()
+        .map(|micros| micros + WEBKIT_EPOCH_OFFSET * 1_000_000)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn to_item(node: Node) -> Item {
+    match node.kind.as_str() {
+        URL => Item::Shortcut(to_bookmark(node)),
+        _ => Item::Subfolder(to_folder(node, false)),
+    }
+}
+
+fn to_folder(node: Node, personal_toolbar_folder: bool) -> Folder {
+    Folder {
+        title: node.name,
+        folded: false,
+        add_date: to_timestamp(&node.date_added),
+        last_modified: to_timestamp(node.date_modified.as_deref().unwrap_or_default()),
+        personal_toolbar_folder,
+        unfiled_bookmarks_folder: false,
+        guid: node.guid,
+        description: String::new(),
+        extra_attributes: IndexMap::new(),
+        children: node
+            .children
+            .unwrap_or_default()
+            .into_iter()
+            .map(to_item)
+            .collect(),
+    }
+}
+
+fn to_bookmark(node: Node) -> Bookmark {
+    Bookmark {
+        href: node.url.unwrap_or_default(),
+        title: node.name,
+        add_date: to_timestamp(&node.date_added),
+        last_visit: to_timestamp(&node.date_last_used),
+        last_modified: Timestamp::default(),
+        icon_uri: String::new(),
+        icon: String::new(),
+        description: String::new(),
+        tags: vec![],
+        keyword: None,
+        post_data: None,
+        guid: node.guid,
+        extra_attributes: IndexMap::new(),
+    }
+}
+
+/// Creates a [Netscape] model from the content of a Chromium `Bookmarks` file.
+pub(crate) fn from_json(raw: &str) -> Result<Netscape, serde_json::Error> {
+    let profile: Profile = serde_json::from_str(raw)?;
+    let roots = profile.roots;
+
+    let mut children = vec![Item::Subfolder(to_folder(roots.bookmark_bar, true))];
+    children.extend(
+        roots
+            .other
+            .children
+            .unwrap_or_default()
+            .into_iter()
+            .map(to_item),
+    );
+
+    if roots
+        .synced
+        .children
+        .as_ref()
+        .is_some_and(|c| !c.is_empty())
+    {
+        children.push(Item::Subfolder(to_folder(roots.synced, false)));
+    }
+
+    Ok(Netscape {
+        title: String::from("Bookmarks"),
+        h1: String::from("Bookmarks"),
+        children,
+    })
+}
+
+/// Computes the checksum of the profile while its nodes are encoded.
+///
+/// It follows the Chromium's `BookmarkCodec` : the nodes are hashed in pre-order with their id,
+/// their name as UTF-16 and either the `folder` keyword or the `url` keyword followed by the url.
+/// The ids `1`, `2` and `3` are reserved to the special folders.
+struct Encoder {
+    ids: u64,
+    md5: Md5,
+}
+
+impl Encoder {
+    fn next_id(&mut self) -> String {
+        self.ids += 1;
+        self.ids.to_string()
+    }
+
+    fn update_name(&mut self, id: &str, name: &str) {
+        self.md5.update(id.as_bytes());
+
+        for unit in name.encode_utf16() {
+            self.md5.update(unit.to_le_bytes());
+        }
+    }
+
+    /// Encodes an item of the model, the separators are dropped since Chromium doesn't support them
+    /// and the feeds and web slices are encoded as bookmarks.
+    fn encode_item(&mut self, item: &Item) -> Option<Node> {
+        match item {
+            Item::Subfolder(folder) => {
+                let id = self.next_id();
+                let children = folder.children.iter().collect::<Vec<_>>();
+
+                Some(self.encode_folder(id, &folder.title, &folder.guid, Some(folder), &children))
+            }
+            Item::Shortcut(bookmark) => Some(self.encode_bookmark(bookmark)),
+            Item::Feed(feed) => Some(self.encode_bookmark(&feed.to_bookmark())),
+            Item::WebSlice(web_slice) => Some(self.encode_bookmark(&web_slice.to_bookmark())),
+            Item::Separator => None,
+        }
+    }
+
+    fn encode_folder(
+        &mut self,
+        id: String,
+        name: &str,
+        guid: &str,
+        folder: Option<&Folder>,
+        children: &[&Item],
+    ) -> Node {
+        self.update_name(&id, name);
+        self.md5.update(FOLDER.as_bytes());
+
+        let default = Timestamp::default();
+        let add_date = folder.map_or(&default, |f| &f.add_date);
+        let last_modified = folder.map_or(&default, |f| &f.last_modified);
+
+        Node {
+            children: Some(
+                children
+                    .iter()
+                    .filter_map(|c| self.encode_item(c))
+                    .collect(),
+            ),
+            date_added: from_timestamp(add_date),
+            date_last_used: String::from("0"),
+            date_modified: Some(from_timestamp(last_modified)),
+            guid: String::from(guid),
+            id,
+            name: String::from(name),
+            kind: String::from(FOLDER),
+            url: None,
+        }
+    }
+
+    fn encode_bookmark(&mut self, bookmark: &Bookmark) -> Node {
+        let id = self.next_id();
+
+        self.update_name(&id, &bookmark.title);
+        self.md5.update(URL.as_bytes());
+        self.md5.update(bookmark.href.as_bytes());
+
+        Node {
+            children: None,
+            date_added: from_timestamp(&bookmark.add_date),
+            date_last_used: from_timestamp(&bookmark.last_visit),
+            date_modified: None,
+            guid: bookmark.guid.clone(),
+            id,
+            name: bookmark.title.clone(),
+            kind: String::from(URL),
+            url: Some(bookmark.href.clone()),
+        }
+    }
+}
+
+fn children_of(folder: Option<&Folder>) -> Vec<&Item> {
+    folder.map_or_else(Vec::new, |f| f.children.iter().collect())
+}
+
+/// Renders a [Netscape] model as a Chromium `Bookmarks` file with its checksum.
+pub(crate) fn to_json(netscape: &Netscape) -> Result<String, serde_json::Error> {
+    let mut bookmark_bar = None;
+    let mut synced = None;
+    let mut other = vec![];
+
+    for item in netscape.children.iter() {
+        match item {
+            Item::Subfolder(f) if f.personal_toolbar_folder && bookmark_bar.is_none() => {
+                bookmark_bar = Some(f)
+            }
+            Item::Subfolder(f) if f.guid == SYNCED_GUID && synced.is_none() => synced = Some(f),
+            _ => other.push(item),
+        }
+    }
+
+    let mut encoder = Encoder {
+        ids: 3,
+        md5: Md5::new(),
+    };
+
+    let roots = Roots {
+        bookmark_bar: encoder.encode_folder(
+            String::from("1"),
+            "Bookmarks bar",
+            BOOKMARK_BAR_GUID,
+            bookmark_bar,
+            &children_of(bookmark_bar),
+        ),
+        other: encoder.encode_folder(
+            String::from("2"),
+            "Other bookmarks",
+            OTHER_GUID,
+            None,
+            &other,
+        ),
+        synced: encoder.encode_folder(
+            String::from("3"),
+            "Mobile bookmarks",
+            SYNCED_GUID,
+            synced,
+            &children_of(synced),
+        ),
+    };
+
+    let checksum = encoder
+        .md5
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    serde_json::to_string_pretty(&Profile {
+        checksum,
+        roots,
+        version: 1,
+    })
+}
+
+#[test]
+fn should_import_chromium_profile() {
+    use std::fs;
+    use std::path::Path;
+
+    let raw = fs::read_to_string("./res/chromium_bookmarks.json").unwrap();
+    let imported = from_json(&raw).unwrap();
+    let exported = Netscape::from_file(Path::new("./res/chromium.html")).unwrap();
+
+    assert_eq!(imported, exported);
+
+    let bar = imported.children[0].take_subfolder().unwrap();
+    assert!(bar.personal_toolbar_folder);
+    assert_eq!(bar.guid, BOOKMARK_BAR_GUID);
+}
+
+#[test]
+fn should_compute_chromium_checksum() {
+    use std::fs;
+
+    let raw = fs::read_to_string("./res/chromium_bookmarks.json").unwrap();
+    let original: Profile = serde_json::from_str(&raw).unwrap();
+
+    let exported = to_json(&from_json(&raw).unwrap()).unwrap();
+    let profile: Profile = serde_json::from_str(&exported).unwrap();
+
+    assert_eq!(profile.checksum, original.checksum);
+    assert_eq!(from_json(&exported).unwrap(), from_json(&raw).unwrap());
+}
+
+#[test]
+fn should_convert_webkit_timestamps() {
+    assert_eq!(
+        to_timestamp("13245384126123456"),
+        Timestamp::from("1600910526")
+    );
+    assert_eq!(to_timestamp("0"), Timestamp::default());
+    assert_eq!(
+        from_timestamp(&Timestamp::from("1600910526")),
+        "13245384126000000"
+    );
+    assert_eq!(from_timestamp(&Timestamp::default()), "0");
+}
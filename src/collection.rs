@@ -1,7 +1,10 @@
 //! Contains all custom selectors for the Vec<Item>
 use crate::item::Item;
+use crate::traverse::Iter;
 use crate::Bookmark;
+use crate::Feed;
 use crate::Folder;
+use crate::WebSlice;
 
 /// Declares the selectors that collects all nested item of a specified type.
 /// It implements a way to collect every items of type from the [Netscape] structure.
@@ -11,40 +14,46 @@ pub trait NestedCollection {
 
     /// Collects all nested [Folder]s of the folder
     fn subfolders(&self) -> Vec<&Folder>;
+
+    /// Collects all nested [Feed]s of the folder
+    fn feeds(&self) -> Vec<&Feed>;
+
+    /// Collects all nested [WebSlice]s of the folder
+    fn web_slices(&self) -> Vec<&WebSlice>;
 }
 
 impl NestedCollection for Vec<Item> {
     fn shortcuts(&self) -> Vec<&Bookmark> {
-        let mut all = vec![];
-
-        for item in self.iter() {
-            match item {
-                Item::Subfolder(f) => all.append(&mut f.children.shortcuts()),
-                Item::Shortcut(b) => all.push(b),
-            }
-        }
-
-        all
+        Iter::new(self)
+            .filter_map(|(_, _, item)| item.take_shortcut())
+            .collect()
     }
 
     fn subfolders(&self) -> Vec<&Folder> {
-        let mut all = vec![];
+        Iter::new(self)
+            .filter_map(|(_, _, item)| item.take_subfolder())
+            .collect()
+    }
 
-        for item in self.iter() {
-            if let Item::Subfolder(f) = item {
-                all.push(f);
-                all.append(&mut f.children.subfolders());
-            }
-        }
+    fn feeds(&self) -> Vec<&Feed> {
+        Iter::new(self)
+            .filter_map(|(_, _, item)| item.take_feed())
+            .collect()
+    }
 
-        all
+    fn web_slices(&self) -> Vec<&WebSlice> {
+        Iter::new(self)
+            .filter_map(|(_, _, item)| item.take_web_slice())
+            .collect()
     }
 }
 
 #[test]
 fn should_get_all_nested_items() {
     use crate::bookmark::BookmarkBuilder;
+    use crate::feed::FeedBuilder;
     use crate::folder::FolderBuilder;
+    use crate::web_slice::WebSliceBuilder;
 
     let b0 = BookmarkBuilder::default()
         .href(String::from("test0"))
@@ -58,13 +67,26 @@ fn should_get_all_nested_items() {
         .build()
         .unwrap();
 
+    let feed = FeedBuilder::default()
+        .feed_url(String::from("feed"))
+        .build()
+        .unwrap();
+
     let f1 = FolderBuilder::default()
-        .children(vec![Item::Shortcut(b1.clone())])
+        .children(vec![Item::Shortcut(b1.clone()), Item::Feed(feed.clone())])
+        .build()
+        .unwrap();
+
+    let web_slice = WebSliceBuilder::default()
+        .href(String::from("slice"))
         .build()
         .unwrap();
 
     let f0 = FolderBuilder::default()
-        .children(vec![Item::Subfolder(f1.clone())])
+        .children(vec![
+            Item::Subfolder(f1.clone()),
+            Item::WebSlice(web_slice.clone()),
+        ])
         .build()
         .unwrap();
 
@@ -78,4 +100,6 @@ fn should_get_all_nested_items() {
 
     assert_eq!(folder.children.shortcuts(), vec![&b0, &b1]);
     assert_eq!(folder.children.subfolders(), vec![&f0, &f1]);
+    assert_eq!(folder.children.feeds(), vec![&feed]);
+    assert_eq!(folder.children.web_slices(), vec![&web_slice]);
 }
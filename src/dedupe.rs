@@ -0,0 +1,428 @@
+//! Contains the deduplication of the bookmarks of a [Netscape] document.
+//!
+//! [Netscape]: crate::Netscape
+use indexmap::IndexMap;
+
+use crate::item::Item;
+use crate::tree;
+use crate::url::{self, Rules};
+use crate::Bookmark;
+use crate::Timestamp;
+
+/// Defines when two bookmarks are considered as duplicates.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum DedupeStrategy {
+    /// The bookmarks have the exact same `href`, it is the default strategy.
+    #[default]
+    Href,
+
+    /// The bookmarks have the same `href` once normalized with the default [Rules], see [url::normalize].
+    NormalizedUrl,
+
+    /// The bookmarks have the same `href` once normalized with the given [Rules].
+    NormalizedUrlWith(Rules),
+
+    /// The bookmarks have the same title, regardless of the case, and the same host.
+    TitleAndHost,
+}
+
+/// Defines which bookmark of a group of duplicates is kept.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DedupePolicy {
+    /// Keeps the first bookmark in the document order, it is the default policy.
+    #[default]
+    KeepFirst,
+
+    /// Keeps the most recently added bookmark, the first one is kept when the `add_date` are equal.
+    KeepNewest,
+
+    /// Keeps the first bookmark and merges the metadata of its duplicates into it :
+    /// the missing fields are filled, the tags are unioned, the earliest `add_date` and the
+    /// latest `last_visit` and `last_modified` are kept.
+    MergeMetadata,
+}
+
+/// Describes a bookmark removed by the deduplication.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemovedBookmark {
+    /// The `bookmark` attribute is the removed [Bookmark].
+    pub bookmark: Bookmark,
+
+    /// The `folder` attribute stores the titles of the folders containing the bookmark, from the root.
+    /// It is empty when the bookmark was a top-level item.
+    pub folder: Vec<String>,
+
+    /// The `kept` attribute stores the `href` of the bookmark that is kept instead.
+    pub kept: String,
+}
+
+/// Reports what the deduplication removed, in the document order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DedupeReport {
+    /// The `removed` [Vec] stores all the removed bookmarks.
+    pub removed: Vec<RemovedBookmark>,
+}
+
+impl DedupeReport {
+    /// Checks if no bookmark was removed.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty()
+    }
+}
+
+/// A bookmark of the document with its location.
+struct Occurrence {
+    path: Vec<usize>,
+    folder: Vec<String>,
+    key: String,
+}
+
+/// Gets the host of the url without its `www.` prefix.
+fn host(href: &str) -> Option<String> {
+    let normalized = url::normalize(href);
+    let (_, rest) = normalized.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+
+    Some(host.split(':').next().unwrap_or(host).to_string())
+}
+
+fn key(bookmark: &Bookmark, strategy: &DedupeStrategy) -> Option<String> {
+    if bookmark.href.trim().is_empty() {
+        return None;
+    }
+
+    let key = match strategy {
+        DedupeStrategy::Href => bookmark.href.clone(),
+        DedupeStrategy::NormalizedUrl => url::normalize(&bookmark.href),
+        DedupeStrategy::NormalizedUrlWith(rules) => rules.normalize(&bookmark.href),
+        DedupeStrategy::TitleAndHost => format!(
+            "{}\n{}",
+            bookmark.title.trim().to_lowercase(),
+            host(&bookmark.href).unwrap_or_else(|| bookmark.href.clone())
+        ),
+    };
+
+    Some(key)
+}
+
+fn collect(
+    children: &[Item],
+    strategy: &DedupeStrategy,
+    path: &mut Vec<usize>,
+    folder: &mut Vec<String>,
+    occurrences: &mut Vec<Occurrence>,
+) {
+    for (index, item) in children.iter().enumerate() {
+        path.push(index);
+
+        match item {
+            Item::Shortcut(bookmark) => {
+                if let Some(key) = key(bookmark, strategy) {
+                    occurrences.push(Occurrence {
+                        path: path.clone(),
+                        folder: folder.clone(),
+                        key,
+                    });
+                }
+            }
+            Item::Subfolder(f) => {
+                folder.push(f.title.clone());
+                collect(&f.children, strategy, path, folder, occurrences);
+                folder.pop();
+            }
+            _ => (),
+        }
+
+        path.pop();
+    }
+}
+
+fn bookmark_at<'a>(children: &'a [Item], path: &[usize]) -> &'a Bookmark {
+    tree::get(children, path)
+        .and_then(Item::take_shortcut)
+        .expect("the occurrences are bookmarks of the tree")
+}
+
+/// Picks the latest or the earliest of two dates, an empty or invalid date is never picked.
+fn pick(current: &Timestamp, other: &Timestamp, latest: bool) -> Timestamp {
+    match (current.as_secs(), other.as_secs()) {
+        (Some(c), Some(o)) if (o > c) == latest && o != c => other.clone(),
+        (None, Some(_)) => other.clone(),
+        _ => current.clone(),
+    }
+}
+
+pub(crate) fn merge_metadata(kept: &mut Bookmark, other: &Bookmark) {
+    if kept.title.is_empty() {
+        kept.title = other.title.clone();
+    }
+
+    if kept.description.is_empty() {
+        kept.description = other.description.clone();
+    }
+
+    if kept.icon.is_empty() {
+        kept.icon = other.icon.clone();
+    }
+
+    if kept.icon_uri.is_empty() {
+        kept.icon_uri = other.icon_uri.clone();
+    }
+
+    if kept.guid.is_empty() {
+        kept.guid = other.guid.clone();
+    }
+
+    if kept.keyword.is_none() {
+        kept.keyword = other.keyword.clone();
+    }
+
+    if kept.post_data.is_none() {
+        kept.post_data = other.post_data.clone();
+    }
+
+    for tag in other.tags.iter() {
+        if !kept.tags.contains(tag) {
+            kept.tags.push(tag.clone());
+        }
+    }
+
+    for (name, value) in other.extra_attributes.iter() {
+        kept.extra_attributes
+            .entry(name.clone())
+            .or_insert_with(|| value.clone());
+    }
+
+    kept.add_date = pick(&kept.add_date, &other.add_date, false);
+    kept.last_visit = pick(&kept.last_visit, &other.last_visit, true);
+    kept.last_modified = pick(&kept.last_modified, &other.last_modified, true);
+}
+
+/// Removes the duplicated bookmarks of the children and reports them.
+pub(crate) fn dedupe(
+    children: &mut Vec<Item>,
+    strategy: DedupeStrategy,
+    policy: DedupePolicy,
+) -> DedupeReport {
+    let mut occurrences = vec![];
+    collect(
+        children,
+        &strategy,
+        &mut vec![],
+        &mut vec![],
+        &mut occurrences,
+    );
+
+    let mut groups: IndexMap<&str, Vec<&Occurrence>> = IndexMap::new();
+
+    for occurrence in occurrences.iter() {
+        groups
+            .entry(occurrence.key.as_str())
+            .or_default()
+            .push(occurrence);
+    }
+
+    let mut removed = vec![];
+
+    for group in groups.values().filter(|group| group.len() > 1) {
+        let mut kept = group[0];
+
+        if policy == DedupePolicy::KeepNewest {
+            for occurrence in group.iter() {
+                let date = bookmark_at(children, &occurrence.path).add_date.as_secs();
+
+                if date > bookmark_at(children, &kept.path).add_date.as_secs() {
+                    kept = occurrence;
+                }
+            }
+        }
+
+        if policy == DedupePolicy::MergeMetadata {
+            let mut merged = bookmark_at(children, &kept.path).clone();
+
+            for occurrence in group.iter().skip(1) {
+                merge_metadata(&mut merged, bookmark_at(children, &occurrence.path));
+            }
+
+            if let Some(Item::Shortcut(bookmark)) = tree::get_mut(children, &kept.path) {
+                *bookmark = merged;
+            }
+        }
+
+        let href = bookmark_at(children, &kept.path).href.clone();
+
+        for occurrence in group.iter().filter(|o| o.path != kept.path) {
+            removed.push((*occurrence, href.clone()));
+        }
+    }
+
+    // the bookmarks are leaves, removing the last ones first keeps the other paths valid
+    removed.sort_by(|(a, _), (b, _)| b.path.cmp(&a.path));
+
+    let mut report = DedupeReport::default();
+
+    for (occurrence, kept) in removed {
+        if let Ok(Item::Shortcut(bookmark)) = tree::remove(children, &occurrence.path) {
+            report.removed.push(RemovedBookmark {
+                bookmark,
+                folder: occurrence.folder.clone(),
+                kept,
+            });
+        }
+    }
+
+    report.removed.reverse();
+    report
+}
+
+#[allow(dead_code)]
+fn mock_children() -> Vec<Item> {
+    use crate::bookmark::BookmarkBuilder;
+    use crate::folder::FolderBuilder;
+
+    let bookmark = |href: &str, title: &str, add_date: &str| {
+        Item::Shortcut(
+            BookmarkBuilder::default()
+                .href(href)
+                .title(title)
+                .add_date(add_date)
+                .build()
+                .unwrap(),
+        )
+    };
+
+    vec![
+        bookmark("https://www.rust-lang.org/", "Rust", "10"),
+        Item::Subfolder(
+            FolderBuilder::default()
+                .title("Dev")
+                .children(vec![
+                    bookmark("https://www.rust-lang.org/", "Rust", "30"),
+                    bookmark("http://rust-lang.org", "Rust Language", "20"),
+                    bookmark("https://rust-lang.org/learn", "rust", "40"),
+                ])
+                .build()
+                .unwrap(),
+        ),
+    ]
+}
+
+#[test]
+fn should_dedupe_by_href() {
+    let mut children = mock_children();
+    let report = dedupe(&mut children, DedupeStrategy::Href, DedupePolicy::KeepFirst);
+
+    assert_eq!(report.removed.len(), 1);
+    assert_eq!(report.removed[0].folder, vec!["Dev"]);
+    assert_eq!(report.removed[0].bookmark.add_date, Timestamp::from("30"));
+    assert_eq!(
+        tree::get(&children, &[1, 0])
+            .unwrap()
+            .take_shortcut()
+            .unwrap()
+            .title,
+        "Rust Language"
+    );
+}
+
+#[test]
+fn should_dedupe_by_normalized_url() {
+    let mut children = mock_children();
+    let report = dedupe(
+        &mut children,
+        DedupeStrategy::NormalizedUrl,
+        DedupePolicy::KeepNewest,
+    );
+
+    let removed = report
+        .removed
+        .iter()
+        .map(|r| r.bookmark.add_date.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(removed, vec!["10", "20"]);
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].take_subfolder().unwrap().children.len(), 2);
+}
+
+#[test]
+fn should_dedupe_by_title_and_host() {
+    let mut children = mock_children();
+    let report = dedupe(
+        &mut children,
+        DedupeStrategy::TitleAndHost,
+        DedupePolicy::KeepFirst,
+    );
+
+    let removed = report
+        .removed
+        .iter()
+        .map(|r| r.bookmark.add_date.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(removed, vec!["30", "40"]);
+    assert!(report
+        .removed
+        .iter()
+        .all(|r| r.kept == "https://www.rust-lang.org/"));
+}
+
+#[test]
+fn should_merge_metadata_of_duplicates() {
+    use crate::bookmark::BookmarkBuilder;
+
+    let first = BookmarkBuilder::default()
+        .href("url")
+        .title("name")
+        .add_date("20")
+        .last_visit("20")
+        .tags(vec![String::from("a")])
+        .build()
+        .unwrap();
+
+    let second = BookmarkBuilder::default()
+        .href("url")
+        .title("other")
+        .add_date("10")
+        .last_visit("30")
+        .icon("icon")
+        .tags(vec![String::from("a"), String::from("b")])
+        .keyword(Some(String::from("kw")))
+        .build()
+        .unwrap();
+
+    let mut children = vec![Item::Shortcut(first), Item::Shortcut(second)];
+    let report = dedupe(
+        &mut children,
+        DedupeStrategy::Href,
+        DedupePolicy::MergeMetadata,
+    );
+
+    assert_eq!(report.removed.len(), 1);
+
+    let merged = children[0].take_shortcut().unwrap();
+    assert_eq!(merged.title, "name");
+    assert_eq!(merged.icon, "icon");
+    assert_eq!(merged.tags, vec!["a", "b"]);
+    assert_eq!(merged.keyword, Some(String::from("kw")));
+    assert_eq!(merged.add_date, Timestamp::from("10"));
+    assert_eq!(merged.last_visit, Timestamp::from("30"));
+}
+
+#[test]
+fn should_dedupe_with_custom_rules() {
+    use crate::url::RulesBuilder;
+
+    let rules = RulesBuilder::default().strip_www(false).build().unwrap();
+
+    let mut children = mock_children();
+    let report = dedupe(
+        &mut children,
+        DedupeStrategy::NormalizedUrlWith(rules),
+        DedupePolicy::KeepFirst,
+    );
+
+    assert_eq!(report.removed.len(), 1);
+    assert_eq!(report.removed[0].bookmark.add_date, Timestamp::from("30"));
+}
@@ -0,0 +1,585 @@
+//! Contains the structural diff between two [Netscape] documents.
+//!
+//! The folders are matched by their title path and the bookmarks by their normalized url, see [url::normalize].
+//! A folder is renamed when a folder of the same parent disappears and the new one shares
+//! a bookmark or a subfolder with it, or both are empty at the same position.
+//!
+//! [Netscape]: crate::Netscape
+//! [url::normalize]: crate::url::normalize
+use indexmap::{IndexMap, IndexSet};
+use std::fmt;
+
+use crate::item::Item;
+use crate::url;
+use crate::Bookmark;
+use crate::Folder;
+
+/// Represents a change between two documents, the folders are given by their title paths from the root.
+///
+/// What exists in the new document only is named as in the new document : the added bookmarks and
+/// folders, and the destination of a move. Everything else is named as in the old document, where the
+/// bookmark or the folder was, even when a folder containing it was renamed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    /// The bookmark only exists in the new document, its folder is named as in the new document.
+    BookmarkAdded {
+        /// The folder containing the bookmark.
+        folder: Vec<String>,
+        /// The added bookmark.
+        bookmark: Bookmark,
+    },
+
+    /// The bookmark only exists in the old document.
+    BookmarkRemoved {
+        /// The folder that contained the bookmark.
+        folder: Vec<String>,
+        /// The removed bookmark.
+        bookmark: Bookmark,
+    },
+
+    /// The bookmark is in another folder.
+    BookmarkMoved {
+        /// The folder that contained the bookmark, named as in the old document.
+        from: Vec<String>,
+        /// The folder containing the bookmark, named as in the new document.
+        to: Vec<String>,
+        /// The moved bookmark, as it is in the new document.
+        bookmark: Bookmark,
+    },
+
+    /// The bookmark has another title.
+    BookmarkRenamed {
+        /// The folder that contained the bookmark, named as in the old document.
+        folder: Vec<String>,
+        /// The `href` of the bookmark.
+        href: String,
+        /// The old title.
+        from: String,
+        /// The new title.
+        to: String,
+    },
+
+    /// The bookmark with the same title in the same folder has another `href`.
+    HrefChanged {
+        /// The folder containing the bookmark, named as in the old document.
+        folder: Vec<String>,
+        /// The title of the bookmark.
+        title: String,
+        /// The old `href`.
+        from: String,
+        /// The new `href`.
+        to: String,
+    },
+
+    /// The folder only exists in the new document.
+    FolderAdded {
+        /// The path of the added folder, named as in the new document.
+        path: Vec<String>,
+    },
+
+    /// The folder only exists in the old document.
+    FolderRemoved {
+        /// The path of the removed folder.
+        path: Vec<String>,
+    },
+
+    /// The folder has another title.
+    FolderRenamed {
+        /// The old path of the folder.
+        path: Vec<String>,
+        /// The new title.
+        to: String,
+    },
+}
+
+fn display_path(path: &[String]) -> String {
+    format!("/{}", path.join("/"))
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::BookmarkAdded { folder, bookmark } => write!(
+                f,
+                "+ {} {:?} <{}>",
+                display_path(folder),
+                bookmark.title,
+                bookmark.href
+            ),
+            Change::BookmarkRemoved { folder, bookmark } => write!(
+                f,
+                "- {} {:?} <{}>",
+                display_path(folder),
+                bookmark.title,
+                bookmark.href
+            ),
+            Change::BookmarkMoved { from, to, bookmark } => write!(
+                f,
+                "> {} -> {} {:?} <{}>",
+                display_path(from),
+                display_path(to),
+                bookmark.title,
+                bookmark.href
+            ),
+            Change::BookmarkRenamed {
+                folder,
+                href,
+                from,
+                to,
+            } => write!(
+                f,
+                "~ {} {:?} -> {:?} <{}>",
+                display_path(folder),
+                from,
+                to,
+                href
+            ),
+            Change::HrefChanged {
+                folder,
+                title,
+                from,
+                to,
+            } => write!(
+                f,
+                "~ {} {:?} <{}> -> <{}>",
+                display_path(folder),
+                title,
+                from,
+                to
+            ),
+            Change::FolderAdded { path } => write!(f, "+ {}/", display_path(path)),
+            Change::FolderRemoved { path } => write!(f, "- {}/", display_path(path)),
+            Change::FolderRenamed { path, to } => {
+                write!(f, "~ {}/ -> {:?}", display_path(path), to)
+            }
+        }
+    }
+}
+
+/// Lists the changes between two documents, the folders changes come first.
+///
+/// It is rendered as a text with one change per line : `+` for the additions, `-` for the removals,
+/// `>` for the moves and `~` for the modifications.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diff {
+    /// The `changes` [Vec] stores the changes in the order of the documents.
+    pub changes: Vec<Change>,
+}
+
+impl Diff {
+    /// Checks if the documents have no differences.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in self.changes.iter() {
+            writeln!(f, "{}", change)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A folder of a document with its position in its parent.
+struct FolderEntry {
+    path: Vec<String>,
+    index: usize,
+    /// The normalized urls of the nested bookmarks and the titles of the subfolders.
+    content: IndexSet<String>,
+}
+
+/// Lists what identifies a folder, the nested bookmarks are kept when their subfolders are renamed too.
+fn content(folder: &Folder) -> IndexSet<String> {
+    let mut content = IndexSet::new();
+
+    for item in folder.children.iter() {
+        match item {
+            Item::Shortcut(b) => {
+                content.insert(url::normalize(&b.href));
+            }
+            Item::Subfolder(f) => {
+                content.insert(format!("{}/", f.title));
+                content.extend(self::content(f));
+            }
+            _ => (),
+        }
+    }
+
+    content
+}
+
+impl FolderEntry {
+    fn looks_like(&self, other: &FolderEntry) -> bool {
+        if self.content.is_empty() && other.content.is_empty() {
+            self.index == other.index
+        } else {
+            !self.content.is_disjoint(&other.content)
+        }
+    }
+}
+
+/// Collects the folders and the bookmarks of a list in the document order.
+fn collect<'a>(
+    children: &'a [Item],
+    path: &mut Vec<String>,
+    folders: &mut Vec<FolderEntry>,
+    bookmarks: &mut Vec<(Vec<String>, &'a Bookmark)>,
+) {
+    for (index, item) in children.iter().enumerate() {
+        match item {
+            Item::Subfolder(folder) => {
+                path.push(folder.title.clone());
+                folders.push(FolderEntry {
+                    path: path.clone(),
+                    index,
+                    content: content(folder),
+                });
+                collect(&folder.children, path, folders, bookmarks);
+                path.pop();
+            }
+            Item::Shortcut(bookmark) => bookmarks.push((path.clone(), bookmark)),
+            _ => (),
+        }
+    }
+}
+
+/// Rewrites a path of the new document with the names of the old document.
+fn translate(path: &[String], renames: &[(Vec<String>, Vec<String>)]) -> Vec<String> {
+    // the old path of a renamed folder already has the old names of its parents
+    let rename = renames
+        .iter()
+        .filter(|(new, _)| path.starts_with(new))
+        .max_by_key(|(new, _)| new.len());
+
+    match rename {
+        Some((new, old)) => old.iter().chain(&path[new.len()..]).cloned().collect(),
+        None => path.to_vec(),
+    }
+}
+
+/// Lists the changes from the old children to the new children.
+pub(crate) fn diff(old: &[Item], new: &[Item]) -> Diff {
+    let (mut old_folders, mut old_bookmarks) = (vec![], vec![]);
+    let (mut new_folders, mut new_bookmarks) = (vec![], vec![]);
+
+    collect(old, &mut vec![], &mut old_folders, &mut old_bookmarks);
+    collect(new, &mut vec![], &mut new_folders, &mut new_bookmarks);
+
+    let mut changes = vec![];
+    let mut renames: Vec<(Vec<String>, Vec<String>)> = vec![];
+    let mut unmatched = old_folders.iter().collect::<Vec<_>>();
+
+    for folder in new_folders.iter() {
+        let translated = translate(&folder.path, &renames);
+
+        if let Some(position) = unmatched.iter().position(|f| f.path == translated) {
+            unmatched.remove(position);
+            continue;
+        }
+
+        let parent = &translated[..translated.len() - 1];
+        let renamed = unmatched.iter().position(|f| {
+            f.path.starts_with(parent)
+                && f.path.len() == translated.len()
+                && f.looks_like(folder)
+                && !new_folders
+                    .iter()
+                    .any(|n| translate(&n.path, &renames) == f.path)
+        });
+
+        match renamed {
+            Some(position) => {
+                let old = unmatched.remove(position);
+
+                changes.push(Change::FolderRenamed {
+                    path: old.path.clone(),
+                    to: folder.path.last().cloned().unwrap_or_default(),
+                });
+                renames.push((folder.path.clone(), old.path.clone()));
+            }
+            None => changes.push(Change::FolderAdded {
+                path: folder.path.clone(),
+            }),
+        }
+    }
+
+    for folder in unmatched {
+        changes.push(Change::FolderRemoved {
+            path: folder.path.clone(),
+        });
+    }
+
+    let mut old_by_url: IndexMap<String, Vec<(Vec<String>, &Bookmark)>> = IndexMap::new();
+
+    for (path, bookmark) in old_bookmarks {
+        old_by_url
+            .entry(url::normalize(&bookmark.href))
+            .or_default()
+            .push((path, bookmark));
+    }
+
+    let mut added = vec![];
+
+    for (path, bookmark) in new_bookmarks {
+        let folder = translate(&path, &renames);
+        let key = url::normalize(&bookmark.href);
+        let matched = old_by_url.get_mut(&key).and_then(|candidates| {
+            // a bookmark that stays in its folder is preferred to a moved one
+            let position = candidates
+                .iter()
+                .position(|(p, _)| *p == folder)
+                .or(if candidates.is_empty() { None } else { Some(0) })?;
+
+            Some(candidates.remove(position))
+        });
+
+        match matched {
+            Some((old_folder, old)) => {
+                if old_folder != folder {
+                    changes.push(Change::BookmarkMoved {
+                        from: old_folder.clone(),
+                        to: path,
+                        bookmark: bookmark.clone(),
+                    });
+                }
+
+                if old.title != bookmark.title {
+                    changes.push(Change::BookmarkRenamed {
+                        folder: old_folder,
+                        href: bookmark.href.clone(),
+                        from: old.title.clone(),
+                        to: bookmark.title.clone(),
+                    });
+                }
+            }
+            None => added.push((folder, path, bookmark)),
+        }
+    }
+
+    let mut removed = old_by_url.into_values().flatten().collect::<Vec<_>>();
+
+    for (folder, path, bookmark) in added {
+        let changed = removed
+            .iter()
+            .position(|(p, old)| *p == folder && old.title == bookmark.title);
+
+        match changed {
+            Some(position) => {
+                let (_, old) = removed.remove(position);
+
+                changes.push(Change::HrefChanged {
+                    folder,
+                    title: bookmark.title.clone(),
+                    from: old.href.clone(),
+                    to: bookmark.href.clone(),
+                });
+            }
+            None => changes.push(Change::BookmarkAdded {
+                folder: path,
+                bookmark: bookmark.clone(),
+            }),
+        }
+    }
+
+    for (folder, bookmark) in removed {
+        changes.push(Change::BookmarkRemoved {
+            folder,
+            bookmark: bookmark.clone(),
+        });
+    }
+
+    Diff { changes }
+}
+
+#[allow(dead_code)]
+fn bookmark(href: &str, title: &str) -> Item {
+    use crate::bookmark::BookmarkBuilder;
+
+    Item::Shortcut(
+        BookmarkBuilder::default()
+            .href(href)
+            .title(title)
+            .build()
+            .unwrap(),
+    )
+}
+
+#[allow(dead_code)]
+fn folder(title: &str, children: Vec<Item>) -> Item {
+    use crate::folder::FolderBuilder;
+
+    Item::Subfolder(
+        FolderBuilder::default()
+            .title(title)
+            .children(children)
+            .build()
+            .unwrap(),
+    )
+}
+
+#[test]
+fn should_diff_identical_documents() {
+    let items = vec![
+        bookmark("https://a.com", "a"),
+        folder("f", vec![bookmark("https://b.com", "b")]),
+    ];
+
+    assert!(diff(&items, &items).is_empty());
+}
+
+#[test]
+fn should_diff_bookmarks() {
+    let old = vec![
+        bookmark("https://a.com", "a"),
+        bookmark("https://b.com", "b"),
+        bookmark("https://c.com", "c"),
+        folder("f", vec![bookmark("https://d.com", "d")]),
+        bookmark("https://e.com", "e"),
+    ];
+    let new = vec![
+        bookmark("http://www.a.com/?utm_source=x", "a"),
+        bookmark("https://b.com", "B"),
+        folder(
+            "f",
+            vec![
+                bookmark("https://d.com", "d"),
+                bookmark("https://c.com", "c"),
+            ],
+        ),
+        bookmark("https://e.org", "e"),
+        bookmark("https://g.com", "g"),
+    ];
+
+    let root = Vec::<String>::new();
+    let f = vec![String::from("f")];
+
+    assert_eq!(
+        diff(&old, &new).changes,
+        vec![
+            Change::BookmarkRenamed {
+                folder: root.clone(),
+                href: String::from("https://b.com"),
+                from: String::from("b"),
+                to: String::from("B"),
+            },
+            Change::BookmarkMoved {
+                from: root.clone(),
+                to: f,
+                bookmark: bookmark("https://c.com", "c").into_shortcut().unwrap(),
+            },
+            Change::HrefChanged {
+                folder: root.clone(),
+                title: String::from("e"),
+                from: String::from("https://e.com"),
+                to: String::from("https://e.org"),
+            },
+            Change::BookmarkAdded {
+                folder: root,
+                bookmark: bookmark("https://g.com", "g").into_shortcut().unwrap(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn should_diff_folders() {
+    let old = vec![
+        folder("dev", vec![bookmark("https://a.com", "a")]),
+        folder("old", vec![]),
+        folder("news", vec![folder("rss", vec![])]),
+    ];
+    let new = vec![
+        folder("development", vec![bookmark("https://a.com", "a")]),
+        folder("journal", vec![folder("rss", vec![])]),
+        folder("new", vec![]),
+    ];
+
+    let diff = diff(&old, &new);
+
+    assert_eq!(
+        diff.changes,
+        vec![
+            Change::FolderRenamed {
+                path: vec![String::from("dev")],
+                to: String::from("development"),
+            },
+            Change::FolderRenamed {
+                path: vec![String::from("news")],
+                to: String::from("journal"),
+            },
+            Change::FolderAdded {
+                path: vec![String::from("new")],
+            },
+            Change::FolderRemoved {
+                path: vec![String::from("old")],
+            },
+        ]
+    );
+    assert_eq!(
+        diff.to_string(),
+        r#"~ /dev/ -> "development"
+~ /news/ -> "journal"
++ /new/
+- /old/
+"#
+    );
+}
+
+#[test]
+fn should_diff_nested_folder_renames() {
+    let old = vec![folder(
+        "a",
+        vec![folder(
+            "b",
+            vec![folder("c", vec![bookmark("https://c.com", "c")])],
+        )],
+    )];
+    let new = vec![folder(
+        "A",
+        vec![folder(
+            "B",
+            vec![folder("c", vec![bookmark("https://c.com", "c")])],
+        )],
+    )];
+
+    assert_eq!(
+        diff(&old, &new).to_string(),
+        "~ /a/ -> \"A\"\n~ /a/b/ -> \"B\"\n"
+    );
+}
+
+#[test]
+fn should_name_additions_as_in_the_new_document() {
+    let old = vec![folder(
+        "Old",
+        vec![
+            bookmark("https://x.com", "x"),
+            bookmark("https://z.com", "z"),
+        ],
+    )];
+    let new = vec![folder(
+        "New",
+        vec![
+            bookmark("https://x.com", "x"),
+            folder(
+                "Sub",
+                vec![
+                    bookmark("https://y.com", "y"),
+                    bookmark("https://z.com", "z"),
+                ],
+            ),
+        ],
+    )];
+
+    assert_eq!(
+        diff(&old, &new).to_string(),
+        r#"~ /Old/ -> "New"
++ /New/Sub/
+> /Old -> /New/Sub "z" <https://z.com>
++ /New/Sub "y" <https://y.com>
+"#
+    );
+}
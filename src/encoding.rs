@@ -0,0 +1,125 @@
+//! Contains the detection of the encoding of a raw document and its decoding.
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+use std::borrow::Cow;
+
+use crate::Error;
+
+/// The number of bytes scanned for a `<META>` charset declaration, like the browsers do.
+const PRESCAN_LENGTH: usize = 1024;
+
+/// Decodes a raw document with the encoding declared by its byte order mark or its `<META>` tag.
+///
+/// An undeclared encoding is UTF-8, unless the document isn't valid UTF-8 : the old exports are
+/// then decoded as Windows-1252. The malformed sequences are replaced by `U+FFFD`.
+pub(crate) fn decode(bytes: &[u8]) -> Cow<'_, str> {
+    let (text, _, _) = detect(bytes).decode(bytes);
+    text
+}
+
+/// Decodes a raw document with a forced encoding, given by its [WHATWG label] like `shift_jis`.
+///
+/// The byte order mark of the forced encoding is removed, the declared encodings are ignored.
+///
+/// [WHATWG label]: https://encoding.spec.whatwg.org/#names-and-labels
+pub(crate) fn decode_with<'a>(bytes: &'a [u8], label: &str) -> Result<Cow<'a, str>, Error> {
+    let encoding = Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| Error::UnknownEncoding(label.to_string()))?;
+    let (text, _) = encoding.decode_with_bom_removal(bytes);
+
+    Ok(text)
+}
+
+/// Detects the encoding of a raw document, the byte order mark is handled by [Encoding::decode].
+fn detect(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+
+    if let Some(encoding) = meta_charset(&bytes[..bytes.len().min(PRESCAN_LENGTH)]) {
+        return encoding;
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        UTF_8
+    } else {
+        WINDOWS_1252
+    }
+}
+
+/// Finds the charset declared by a `<META CHARSET>` or a `<META HTTP-EQUIV="Content-Type">` tag.
+///
+/// The UTF-16 declarations are read as UTF-8, since an ASCII `<META>` tag can't be UTF-16.
+fn meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let lowercase = bytes.to_ascii_lowercase();
+    let mut rest = lowercase.as_slice();
+
+    while let Some(start) = find(rest, b"<meta") {
+        let tag = &rest[start..];
+        let tag = &tag[..find(tag, b">").unwrap_or(tag.len())];
+
+        if let Some(encoding) = find(tag, b"charset").and_then(|at| charset_value(&tag[at + 7..])) {
+            return Some(encoding.output_encoding());
+        }
+
+        rest = &rest[start + 5..];
+    }
+
+    None
+}
+
+/// Reads the encoding after a `charset` keyword, like `="windows-1252"` or `=shift_jis"`.
+fn charset_value(bytes: &[u8]) -> Option<&'static Encoding> {
+    let value = bytes
+        .trim_ascii_start()
+        .strip_prefix(b"=")?
+        .trim_ascii_start();
+    let value = value
+        .strip_prefix(b"\"")
+        .or_else(|| value.strip_prefix(b"'"))
+        .unwrap_or(value);
+    let end = value
+        .iter()
+        .position(|b| matches!(b, b'"' | b'\'' | b';' | b'>' | b'/') || b.is_ascii_whitespace())
+        .unwrap_or(value.len());
+
+    Encoding::for_label(&value[..end])
+}
+
+/// Finds the position of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[test]
+fn should_detect_meta_charset() {
+    let content_type =
+        br#"<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=Shift_JIS"><TITLE>"#;
+    let charset = b"<meta name=x><meta charset='windows-1252'/>";
+
+    assert_eq!(meta_charset(content_type), Some(encoding_rs::SHIFT_JIS));
+    assert_eq!(meta_charset(charset), Some(WINDOWS_1252));
+    assert_eq!(meta_charset(b"<meta charset=utf-16>"), Some(UTF_8));
+    assert_eq!(meta_charset(b"<meta charset=unknown>"), None);
+    assert_eq!(meta_charset(b"<title>charset=shift_jis</title>"), None);
+}
+
+#[test]
+fn should_decode_raw_documents() {
+    let caf = b"<TITLE>caf\xe9</TITLE>";
+
+    assert_eq!(decode(caf), "<TITLE>café</TITLE>");
+    assert_eq!(
+        decode("<TITLE>café</TITLE>".as_bytes()),
+        "<TITLE>café</TITLE>"
+    );
+    assert_eq!(decode(b"\xef\xbb\xbf<TITLE>"), "<TITLE>");
+    assert_eq!(decode(b"\xff\xfe<\0T\0>\0"), "<T>");
+    assert_eq!(decode_with(caf, "latin1").unwrap(), "<TITLE>café</TITLE>");
+    assert_eq!(decode_with(b"\x83n", "sjis").unwrap(), "ハ");
+    assert!(matches!(
+        decode_with(caf, "klingon"),
+        Err(Error::UnknownEncoding(label)) if label == "klingon"
+    ));
+}
@@ -0,0 +1,108 @@
+//! Contains the [Error] enum returned by the fallible operations of the crate.
+use std::fmt;
+use std::io;
+
+/// Represents all the failures that can happen while parsing or rendering a bookmark document.
+///
+/// The lenient parsing only fails on [Error::Io], the other parsing errors are
+/// reported by the [ParseMode::Strict] mode.
+///
+/// [ParseMode::Strict]: crate::ParseMode::Strict
+#[derive(Debug)]
+pub enum Error {
+    /// The document couldn't be read.
+    Io(io::Error),
+
+    /// The document doesn't start with a `<!DOCTYPE NETSCAPE-Bookmark-file-1>` declaration.
+    MissingDoctype,
+
+    /// The document declares another DOCTYPE than `NETSCAPE-Bookmark-file-1`, it stores the found name.
+    InvalidDoctype(String),
+
+    /// The document has no root `<DL>` list holding the items.
+    MissingRootList,
+
+    /// A `<DT>` element is neither a shortcut nor a subfolder, it stores the text of the element.
+    MalformedItem(String),
+
+    /// The document couldn't be rendered.
+    Render(askama::Error),
+
+    /// The JSON representation couldn't be deserialized.
+    Json(serde_json::Error),
+
+    /// No item can be found or inserted at the given path of indexes, it stores the path.
+    InvalidPath(Vec<usize>),
+
+    /// A folder is closed by a [HtmlWriter] while no folder is open.
+    ///
+    /// [HtmlWriter]: crate::HtmlWriter
+    NoOpenFolder,
+
+    /// The forced encoding of a document isn't known, it stores the given label.
+    UnknownEncoding(String),
+
+    /// An icon isn't a valid image, it stores the reason.
+    InvalidIcon(String),
+
+    /// The validation of a document found errors, it stores their [Diagnostic]s.
+    ///
+    /// [Diagnostic]: crate::Diagnostic
+    Invalid(Vec<crate::Diagnostic>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "cannot read the document: {}", err),
+            Error::MissingDoctype => write!(f, "missing NETSCAPE-Bookmark-file-1 doctype"),
+            Error::InvalidDoctype(name) => write!(f, "invalid doctype {:?}", name),
+            Error::MissingRootList => write!(f, "missing root <DL> list"),
+            Error::MalformedItem(text) => write!(f, "malformed item {:?}", text),
+            Error::Render(err) => write!(f, "cannot render the document: {}", err),
+            Error::Json(err) => write!(f, "invalid JSON document: {}", err),
+            Error::InvalidPath(path) => write!(f, "invalid item path {:?}", path),
+            Error::NoOpenFolder => write!(f, "no open folder to close"),
+            Error::UnknownEncoding(label) => write!(f, "unknown encoding {:?}", label),
+            Error::InvalidIcon(reason) => write!(f, "invalid icon: {}", reason),
+            Error::Invalid(diagnostics) => {
+                write!(f, "invalid document")?;
+
+                for diagnostic in diagnostics {
+                    write!(f, "\n{}", diagnostic)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Render(err) => Some(err),
+            Error::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<askama::Error> for Error {
+    fn from(err: askama::Error) -> Self {
+        Error::Render(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
@@ -0,0 +1,44 @@
+//! Contains the [Html] escaper used by the templates of the Netscape Bookmark File format.
+use askama_escape::Escaper;
+use std::fmt;
+
+/// Escapes the text contents and the attribute values of a Netscape Bookmark File.
+///
+/// Unlike the askama's default HTML escaper, it keeps the `/` and `'` characters untouched :
+/// the attributes are always double-quoted and the urls stay readable.
+pub struct Html;
+
+impl Escaper for Html {
+    fn write_escaped_bytes(&self, fmt: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+        let mut start = 0;
+
+        for (i, byte) in bytes.iter().enumerate() {
+            let escaped = match byte {
+                b'&' => "&amp;",
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                b'"' => "&quot;",
+                _ => continue,
+            };
+
+            // the escaped characters are ASCII, the slices are valid UTF-8 boundaries
+            fmt.write_str(std::str::from_utf8(&bytes[start..i]).map_err(|_| fmt::Error)?)?;
+            fmt.write_str(escaped)?;
+            start = i + 1;
+        }
+
+        fmt.write_str(std::str::from_utf8(&bytes[start..]).map_err(|_| fmt::Error)?)
+    }
+}
+
+#[test]
+fn should_escape_markup_characters() {
+    let escaped = askama_escape::escape(r#"<a href="x">Q&A</a>"#, Html).to_string();
+    assert_eq!(escaped, "&lt;a href=&quot;x&quot;&gt;Q&amp;A&lt;/a&gt;");
+}
+
+#[test]
+fn should_keep_urls_readable() {
+    let url = "https://duckduckgo.com/?q=l'été";
+    assert_eq!(askama_escape::escape(url, Html).to_string(), url);
+}
@@ -0,0 +1,193 @@
+//! Contains the [Feed] model and its associated tests.
+use askama::Template;
+use indexmap::IndexMap;
+use kuchiki::NodeRef;
+use serde::{Deserialize, Serialize};
+
+use crate::node_ref_ext::*;
+use crate::Bookmark;
+use crate::Timestamp;
+
+/// The attributes of the `<A/>` tag that are parsed into the fields of a [Feed].
+const KNOWN_ATTRIBUTES: &[&str] = &["HREF", "FEED", "FEEDURL", "ADD_DATE", "LAST_MODIFIED"];
+
+/// Implements the specification of the `feed` item, a RSS or Atom feed also known as a live bookmark.
+///
+/// A feed is a shortcut flagged with the `FEED="true"` attribute, its `FEEDURL` attribute is the url of the feed.
+#[derive(Serialize, Deserialize, Builder, Clone, Debug, Default, Template)]
+#[builder(setter(into))]
+#[serde(deny_unknown_fields)]
+#[template(path = "feed.j2", escape = "netscape")]
+pub struct Feed {
+    /// The `feed_url` attribute stores the url of the RSS or Atom document, it is the `FEEDURL` attribute.
+    pub feed_url: String,
+
+    /// The `href` attribute stores the url of the site that publishes the feed.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub href: String,
+
+    /// The `title` attribute stores the feed's title, it is the `<A/>` content.
+    #[builder(default)]
+    pub title: String,
+
+    /// The `add_date` attribute is the date when the item was created, see [Timestamp].
+    #[builder(default)]
+    pub add_date: Timestamp,
+
+    /// The `last_modified` attribute is the date of the last modification of the item, see [Timestamp].
+    #[builder(default)]
+    pub last_modified: Timestamp,
+
+    /// The `description` attribute stores the note of the feed, it is the content of the following `<DD/>` tag.
+    /// It is plain text like [Bookmark::description], the nested tags are dropped.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+
+    /// The `extra_attributes` map stores the attributes of the `<A/>` tag that aren't modeled.
+    /// Their names are uppercased and they keep the document's order.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub extra_attributes: IndexMap<String, String>,
+}
+
+impl Feed {
+    /// Creates a [Feed] model from a parsed HTML DOM, the `<A/>` tag must be flagged with `FEED="true"`.
+    pub fn from_node(node: &NodeRef) -> Option<Self> {
+        let mut feed = None;
+
+        if node.is_element("DT") {
+            let a = node.children().find(|n| n.is_element("A"));
+
+            if let Some(node) = a {
+                feed = Feed::from_node(&node);
+            }
+        } else if node.is_element("A") && node.has_flag("FEED") {
+            let mut builder = FeedBuilder::default();
+
+            if let Some(attribute) = node.select_attribute("FEEDURL") {
+                builder.feed_url(attribute.value);
+            }
+
+            if let Some(attribute) = node.select_attribute("HREF") {
+                builder.href(attribute.value);
+            }
+
+            if let Some(attribute) = node.select_attribute("ADD_DATE") {
+                builder.add_date(attribute.value);
+            }
+
+            if let Some(attribute) = node.select_attribute("LAST_MODIFIED") {
+                builder.last_modified(attribute.value);
+            }
+
+            builder.extra_attributes(node.select_extra_attributes(KNOWN_ATTRIBUTES));
+            builder.title(node.text_contents());
+
+            if let Some(dd) = node.select_description() {
+                builder.description(dd.description_text());
+            }
+
+            if let Ok(built) = builder.build() {
+                feed = Some(built);
+            }
+        }
+
+        feed
+    }
+
+    /// Converts the feed to a [Bookmark] of its site, or of the feed itself when the site is unknown.
+    ///
+    /// It is used by the formats that don't support feeds, like the browsers' JSON files.
+    pub fn to_bookmark(&self) -> Bookmark {
+        let href = if self.href.is_empty() {
+            &self.feed_url
+        } else {
+            &self.href
+        };
+
+        Bookmark {
+            href: href.clone(),
+            title: self.title.clone(),
+            add_date: self.add_date.clone(),
+            last_modified: self.last_modified.clone(),
+            description: self.description.clone(),
+            extra_attributes: self.extra_attributes.clone(),
+            ..Bookmark::default()
+        }
+    }
+}
+
+impl PartialEq for Feed {
+    fn eq(&self, other: &Self) -> bool {
+        self.feed_url == other.feed_url
+            && self.href == other.href
+            && self.add_date == other.add_date
+            && self.last_modified == other.last_modified
+            && self.title == other.title
+            && self.description == other.description
+            && self.extra_attributes == other.extra_attributes
+    }
+}
+
+#[test]
+fn should_parse_netscape_feed() {
+    use kuchiki::parse_html;
+    use kuchiki::traits::TendrilSink;
+
+    let item = r#"<DT><A HREF="https://blog.rust-lang.org/" FEED="true" FEEDURL="https://blog.rust-lang.org/feed.xml" ADD_DATE="date">Rust Blog</A>"#;
+    let a = parse_html().one(item).select_first("A").unwrap();
+
+    assert_eq!(
+        Feed::from_node(a.as_node()).unwrap(),
+        FeedBuilder::default()
+            .feed_url("https://blog.rust-lang.org/feed.xml")
+            .href("https://blog.rust-lang.org/")
+            .title("Rust Blog")
+            .add_date("date")
+            .build()
+            .unwrap()
+    );
+}
+
+#[test]
+fn should_not_parse_netscape_bookmark_as_feed() {
+    use kuchiki::parse_html;
+    use kuchiki::traits::TendrilSink;
+
+    let item = r#"<DT><A HREF="url" FEED="false" FEEDURL="url/feed">name</A>"#;
+    let a = parse_html().one(item).select_first("A").unwrap();
+
+    assert!(Feed::from_node(a.as_node()).is_none());
+}
+
+#[test]
+fn should_render_feed_html() {
+    let feed = FeedBuilder::default()
+        .feed_url("https://example.com/atom.xml")
+        .href("https://example.com/")
+        .title("Example")
+        .add_date("date")
+        .build()
+        .unwrap();
+
+    let rendered = r#"<DT><A HREF="https://example.com/" FEED="true" FEEDURL="https://example.com/atom.xml" ADD_DATE="date">Example</A>"#;
+    assert_eq!(feed.render().unwrap(), rendered);
+}
+
+#[test]
+fn should_serialize_json_feed() {
+    let json =
+        r#"{"feed_url":"feed","href":"url","title":"name","add_date":"date","last_modified":""}"#;
+    let feed = FeedBuilder::default()
+        .feed_url("feed")
+        .href("url")
+        .title("name")
+        .add_date("date")
+        .build()
+        .unwrap();
+
+    assert_eq!(serde_json::to_string(&feed).unwrap(), json);
+    assert_eq!(serde_json::from_str::<Feed>(json).unwrap(), feed);
+}
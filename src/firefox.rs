@@ -0,0 +1,440 @@
+//! Contains the conversions between the [Netscape] model and the Firefox JSON backups (`bookmarks-*.json`).
+//!
+//! A backup is a tree of *places* whose root holds the special folders of Firefox :
+//! * the `menu________` folder, its children are the top-level items of the [Netscape] document ;
+//! * the `toolbar_____` folder, it is mapped to the [Folder] flagged as `personal_toolbar_folder` ;
+//! * the `unfiled_____` folder, it is mapped to the [Folder] flagged as `unfiled_bookmarks_folder` ;
+//! * the `mobile______` folder, it is mapped to a regular [Folder] when it isn't empty.
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::item::Item;
+use crate::Bookmark;
+use crate::Folder;
+use crate::Netscape;
+use crate::Timestamp;
+
+const CONTAINER: &str = "text/x-moz-place-container";
+const PLACE: &str = "text/x-moz-place";
+const SEPARATOR: &str = "text/x-moz-place-separator";
+
+const ROOT_GUID: &str = "root________";
+const MENU_GUID: &str = "menu________";
+const TOOLBAR_GUID: &str = "toolbar_____";
+const UNFILED_GUID: &str = "unfiled_____";
+const MOBILE_GUID: &str = "mobile______";
+const TAGS_GUID: &str = "tags________";
+
+/// Represents a node of the Firefox backup, either a container, a place or a separator.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct Place {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    guid: String,
+
+    #[serde(default)]
+    title: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date_added: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    type_code: Option<u8>,
+
+    #[serde(default, rename = "iconuri", skip_serializing_if = "Option::is_none")]
+    icon_uri: Option<String>,
+
+    #[serde(rename = "type")]
+    kind: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    root: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uri: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tags: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    keyword: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    post_data: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    children: Vec<Place>,
+}
+
+/// Converts a Firefox timestamp (in microseconds) to a [Timestamp] in seconds, the sub-second part is
+/// dropped like in the HTML exports of Firefox.
+fn to_timestamp(micros: Option<u64>) -> Timestamp {
+    micros
+        .map(|micros| Timestamp::from((micros / 1_000_000).to_string()))
+        .unwrap_or_default()
+}
+
+fn to_item(place: Place) -> Option<Item> {
+    match place.kind.as_str() {
+        CONTAINER => Some(Item::Subfolder(to_folder(place))),
+        PLACE => Some(Item::Shortcut(to_bookmark(place))),
+        SEPARATOR => Some(Item::Separator),
+        _ => None,
+    }
+}
+
+fn to_folder(place: Place) -> Folder {
+    let (title, personal_toolbar_folder, unfiled_bookmarks_folder) = match place.guid.as_str() {
+        TOOLBAR_GUID => (String::from("Bookmarks Toolbar"), true, false),
+        UNFILED_GUID => (String::from("Other Bookmarks"), false, true),
+        MOBILE_GUID => (String::from("Mobile Bookmarks"), false, false),
+        _ => (place.title, false, false),
+    };
+
+    Folder {
+        title,
+        folded: false,
+        add_date: to_timestamp(place.date_added),
+        last_modified: to_timestamp(place.last_modified),
+        personal_toolbar_folder,
+        unfiled_bookmarks_folder,
+        guid: place.guid,
+        description: String::new(),
+        extra_attributes: IndexMap::new(),
+        children: place.children.into_iter().filter_map(to_item).collect(),
+    }
+}
+
+fn to_bookmark(place: Place) -> Bookmark {
+    let tags = place
+        .tags
+        .map(|tags| {
+            tags.split(',')
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Bookmark {
+        href: place.uri.unwrap_or_default(),
+        title: place.title,
+        add_date: to_timestamp(place.date_added),
+        last_visit: Timestamp::default(),
+        last_modified: to_timestamp(place.last_modified),
+        icon_uri: place.icon_uri.unwrap_or_default(),
+        icon: String::new(),
+        description: String::new(),
+        extra_attributes: IndexMap::new(),
+        tags,
+        keyword: place.keyword,
+        post_data: place.post_data,
+        guid: place.guid,
+    }
+}
+
+/// Creates a [Netscape] model from the content of a Firefox JSON backup.
+pub(crate) fn from_json(raw: &str) -> Result<Netscape, serde_json::Error> {
+    let root: Place = serde_json::from_str(raw)?;
+    let mut children = vec![];
+    let mut roots = vec![];
+
+    for place in root.children {
+        match place.guid.as_str() {
+            MENU_GUID => children.extend(place.children.into_iter().filter_map(to_item)),
+            MOBILE_GUID if place.children.is_empty() => (),
+            // the tags are read from the `tags` field of each place, the tag folders duplicate them
+            TAGS_GUID => (),
+            _ => roots.extend(to_item(place)),
+        }
+    }
+
+    children.append(&mut roots);
+
+    Ok(Netscape {
+        title: String::from("Bookmarks"),
+        h1: String::from("Bookmarks Menu"),
+        children,
+    })
+}
+
+/// Assigns the `id` of the places, the ids are unique in the backup and the root is the first one.
+struct Ids(usize);
+
+impl Ids {
+    fn next(&mut self) -> usize {
+        self.0 += 1;
+        self.0
+    }
+}
+
+fn from_item(item: &Item, index: usize, ids: &mut Ids) -> Place {
+    match item {
+        Item::Subfolder(folder) => from_folder(folder, index, ids),
+        Item::Shortcut(bookmark) => from_bookmark(bookmark, index, ids),
+        Item::Feed(feed) => from_bookmark(&feed.to_bookmark(), index, ids),
+        Item::WebSlice(web_slice) => from_bookmark(&web_slice.to_bookmark(), index, ids),
+        Item::Separator => Place {
+            index: Some(index),
+            id: Some(ids.next()),
+            type_code: Some(3),
+            kind: String::from(SEPARATOR),
+            ..Place::default()
+        },
+    }
+}
+
+fn from_children(children: &[&Item], ids: &mut Ids) -> Vec<Place> {
+    children
+        .iter()
+        .enumerate()
+        .map(|(index, item)| from_item(item, index, ids))
+        .collect()
+}
+
+fn from_folder(folder: &Folder, index: usize, ids: &mut Ids) -> Place {
+    let id = ids.next();
+    let children = folder.children.iter().collect::<Vec<_>>();
+
+    Place {
+        guid: folder.guid.clone(),
+        title: folder.title.clone(),
+        index: Some(index),
+        date_added: folder.add_date.as_micros(),
+        last_modified: folder.last_modified.as_micros(),
+        id: Some(id),
+        type_code: Some(2),
+        kind: String::from(CONTAINER),
+        children: from_children(&children, ids),
+        ..Place::default()
+    }
+}
+
+fn from_bookmark(bookmark: &Bookmark, index: usize, ids: &mut Ids) -> Place {
+    let tags = if bookmark.tags.is_empty() {
+        None
+    } else {
+        Some(bookmark.tags.join(","))
+    };
+
+    let icon_uri = if bookmark.icon_uri.is_empty() {
+        None
+    } else {
+        Some(bookmark.icon_uri.clone())
+    };
+
+    Place {
+        guid: bookmark.guid.clone(),
+        title: bookmark.title.clone(),
+        index: Some(index),
+        date_added: bookmark.add_date.as_micros(),
+        last_modified: bookmark.last_modified.as_micros(),
+        id: Some(ids.next()),
+        type_code: Some(1),
+        icon_uri,
+        kind: String::from(PLACE),
+        uri: Some(bookmark.href.clone()),
+        tags,
+        keyword: bookmark.keyword.clone(),
+        post_data: bookmark.post_data.clone(),
+        ..Place::default()
+    }
+}
+
+fn children_of(folder: Option<&Folder>) -> Vec<&Item> {
+    folder
+        .map(|f| f.children.iter().collect())
+        .unwrap_or_default()
+}
+
+/// Creates a special folder of the backup, its dates are the ones of the mapped [Folder] if any.
+fn special_root(
+    guid: &str,
+    title: &str,
+    root: &str,
+    folder: Option<&Folder>,
+    children: &[&Item],
+    index: usize,
+    ids: &mut Ids,
+) -> Place {
+    let id = ids.next();
+
+    Place {
+        guid: String::from(guid),
+        title: String::from(title),
+        index: Some(index),
+        date_added: folder.and_then(|f| f.add_date.as_micros()),
+        last_modified: folder.and_then(|f| f.last_modified.as_micros()),
+        id: Some(id),
+        type_code: Some(2),
+        kind: String::from(CONTAINER),
+        root: Some(String::from(root)),
+        children: from_children(children, ids),
+        ..Place::default()
+    }
+}
+
+/// Renders a [Netscape] model as a Firefox JSON backup.
+pub(crate) fn to_json(netscape: &Netscape) -> Result<String, serde_json::Error> {
+    let mut menu = vec![];
+    let mut toolbar = None;
+    let mut unfiled = None;
+    let mut mobile = None;
+
+    for item in netscape.children.iter() {
+        match item {
+            Item::Subfolder(f) if f.personal_toolbar_folder && toolbar.is_none() => {
+                toolbar = Some(f)
+            }
+            Item::Subfolder(f) if f.unfiled_bookmarks_folder && unfiled.is_none() => {
+                unfiled = Some(f)
+            }
+            Item::Subfolder(f) if f.guid == MOBILE_GUID && mobile.is_none() => mobile = Some(f),
+            _ => menu.push(item),
+        }
+    }
+
+    let mut ids = Ids(0);
+    let root_id = ids.next();
+
+    let roots = vec![
+        special_root(
+            MENU_GUID,
+            "menu",
+            "bookmarksMenuFolder",
+            None,
+            &menu,
+            0,
+            &mut ids,
+        ),
+        special_root(
+            TOOLBAR_GUID,
+            "toolbar",
+            "toolbarFolder",
+            toolbar,
+            &children_of(toolbar),
+            1,
+            &mut ids,
+        ),
+        special_root(
+            UNFILED_GUID,
+            "unfiled",
+            "unfiledBookmarksFolder",
+            unfiled,
+            &children_of(unfiled),
+            3,
+            &mut ids,
+        ),
+        special_root(
+            MOBILE_GUID,
+            "mobile",
+            "mobileFolder",
+            mobile,
+            &children_of(mobile),
+            4,
+            &mut ids,
+        ),
+    ];
+
+    let root = Place {
+        guid: String::from(ROOT_GUID),
+        index: Some(0),
+        id: Some(root_id),
+        type_code: Some(2),
+        kind: String::from(CONTAINER),
+        root: Some(String::from("placesRoot")),
+        children: roots,
+        ..Place::default()
+    };
+
+    serde_json::to_string(&root)
+}
+
+#[test]
+fn should_import_firefox_backup() {
+    use crate::collection::NestedCollection;
+    use std::fs;
+
+    let raw = fs::read_to_string("./res/firefox_backup.json").unwrap();
+    let netscape = from_json(&raw).unwrap();
+
+    let titles = netscape
+        .children
+        .iter()
+        .map(|item| match item {
+            Item::Subfolder(f) => f.title.as_str(),
+            Item::Shortcut(b) => b.title.as_str(),
+            Item::Feed(f) => f.title.as_str(),
+            Item::WebSlice(w) => w.title.as_str(),
+            Item::Separator => "",
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        titles,
+        vec!["Mozilla Firefox", "Bookmarks Toolbar", "Other Bookmarks"]
+    );
+
+    let toolbar = netscape.children[1].take_subfolder().unwrap();
+    assert!(toolbar.personal_toolbar_folder);
+    assert_eq!(toolbar.guid, TOOLBAR_GUID);
+
+    let unfiled = netscape.children[2].take_subfolder().unwrap();
+    assert!(unfiled.unfiled_bookmarks_folder);
+
+    let bookmarks = netscape.children.shortcuts();
+    assert_eq!(bookmarks.len(), 9);
+    assert_eq!(bookmarks[7].tags, vec!["rust"]);
+    assert_eq!(bookmarks[0].guid, "GIfy6apmufPe");
+    assert_eq!(bookmarks[0].add_date, Timestamp::from("1591573917"));
+    assert_eq!(
+        bookmarks[0].icon_uri,
+        "fake-favicon-uri:https://support.mozilla.org/en-US/products/firefox"
+    );
+}
+
+#[test]
+fn should_map_firefox_tags_and_keyword() {
+    let raw = r#"{"guid":"root________","type":"text/x-moz-place-container","children":[
+        {"guid":"menu________","type":"text/x-moz-place-container","children":[
+            {"guid":"abcdefghijkl","title":"t","type":"text/x-moz-place","uri":"https://example.com",
+             "tags":"rust, web","keyword":"ex","dateAdded":1601046314048000},
+            {"guid":"separator000","type":"text/x-moz-place-separator"}
+        ]}
+    ]}"#;
+    let netscape = from_json(raw).unwrap();
+    let bookmark = netscape.children[0].take_shortcut().unwrap();
+
+    assert_eq!(netscape.children.len(), 2);
+    assert!(netscape.children[1].is_separator());
+    assert_eq!(bookmark.tags, vec!["rust", "web"]);
+    assert_eq!(bookmark.keyword, Some(String::from("ex")));
+
+    let exported = to_json(&netscape).unwrap();
+    assert!(exported.contains(r#""tags":"rust,web","keyword":"ex""#));
+    // the dates are truncated to seconds
+    assert!(exported.contains(r#""dateAdded":1601046314000000"#));
+    assert!(exported.contains(r#""typeCode":3,"type":"text/x-moz-place-separator""#));
+}
+
+#[test]
+fn should_roundtrip_firefox_backup() {
+    use std::fs;
+
+    let raw = fs::read_to_string("./res/firefox_backup.json").unwrap();
+    let imported = from_json(&raw).unwrap();
+    let exported = to_json(&imported).unwrap();
+    let reimported = from_json(&exported).unwrap();
+
+    assert_eq!(imported, reimported);
+    assert_eq!(imported.to_json().unwrap(), reimported.to_json().unwrap());
+}
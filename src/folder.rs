@@ -1,15 +1,31 @@
 //! Contains the [Folder] model and its associated tests.
 use askama::Template;
+use indexmap::IndexMap;
 use kuchiki::NodeRef;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::item::Item;
+use crate::bookmark_path::{self, BookmarkPath, Paths};
+use crate::item::{collect_items, Item};
 use crate::node_ref_ext::*;
+use crate::traverse::{self, Iter, IterMut, Visitor, VisitorMut};
+use crate::tree;
+use crate::Error;
+use crate::Timestamp;
+
+/// The attributes of the `<H3/>` tag that are parsed into the fields of a [Folder].
+const KNOWN_ATTRIBUTES: &[&str] = &[
+    "FOLDED",
+    "ADD_DATE",
+    "LAST_MODIFIED",
+    "PERSONAL_TOOLBAR_FOLDER",
+    "UNFILED_BOOKMARKS_FOLDER",
+];
 
 /// Parses the item formated as a `subfolder`. By nature, the [Folder] are nested structures.
-#[derive(Serialize, Clone, Builder, Debug, Default, Template)]
-#[template(path = "folder.j2", escape = "none")]
+#[derive(Serialize, Deserialize, Clone, Builder, Debug, Default, Template)]
+#[template(path = "folder.j2", escape = "netscape")]
 #[builder(setter(into))]
+#[serde(deny_unknown_fields)]
 pub struct Folder {
     /// The `title` represents the subfolder's title, it is usually encapsulated by an `<H3/>` tag.
     #[builder(default)]
@@ -19,13 +35,13 @@ pub struct Folder {
     #[builder(default = "false")]
     pub folded: bool,
 
-    /// The `add_date` attribute is the date when the item was created (in UNIX time).
+    /// The `add_date` attribute is the date when the item was created, see [Timestamp].
     #[builder(default)]
-    pub add_date: String,
+    pub add_date: Timestamp,
 
-    /// The `last_modified` attribute is the date of the last modification of the item (in UNIX time).
+    /// The `last_modified` attribute is the date of the last modification of the item, see [Timestamp].
     #[builder(default)]
-    pub last_modified: String,
+    pub last_modified: Timestamp,
 
     /// The `personal_toolbar_folder` attribute represents if a folder is the bookmark toolbar folder.
     #[builder(default = "false")]
@@ -35,6 +51,23 @@ pub struct Folder {
     #[builder(default = "false")]
     pub unfiled_bookmarks_folder: bool,
 
+    /// The `guid` attribute stores the browser's identifier of the item, it isn't part of the Netscape format.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub guid: String,
+
+    /// The `description` attribute stores the note of the subfolder, it is the content of the following `<DD/>` tag.
+    /// It is plain text like [Bookmark::description](crate::Bookmark::description), the nested tags are dropped.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+
+    /// The `extra_attributes` map stores the attributes of the `<H3/>` tag that aren't modeled,
+    /// like `ID`. Their names are uppercased and they keep the document's order.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub extra_attributes: IndexMap<String, String>,
+
     /// In contrast to the other items, a [Folder] has a `children` attribute that contains all its nested items.
     /// The `children` [Vec] stores all the subfolder's items in the **same** order than the initial bookmarks organization.
     #[builder(default)]
@@ -75,20 +108,23 @@ impl Folder {
                 builder.unfiled_bookmarks_folder(true);
             }
 
+            builder.extra_attributes(node.select_extra_attributes(KNOWN_ATTRIBUTES));
             builder.title(node.text_contents());
 
-            for sibling in node.following_siblings() {
-                if sibling.is_element("DL") {
-                    let mut children = vec![];
+            let dd = node.select_description();
+
+            if let Some(dd) = &dd {
+                builder.description(dd.description_text());
+            }
 
-                    for child in sibling.children() {
-                        if let Some(item) = Item::from_node(&child) {
-                            children.push(item)
-                        }
-                    }
+            // the list follows the <H3/> tag, or is nested in the <DD/> tag when there is a description
+            let list = node
+                .following_siblings()
+                .chain(dd.iter().flat_map(|dd| dd.children()))
+                .find(|n| n.is_element("DL"));
 
-                    builder.children(children);
-                }
+            if let Some(list) = list {
+                builder.children(collect_items(&list));
             }
 
             if let Ok(built) = builder.build() {
@@ -98,12 +134,115 @@ impl Folder {
 
         folder
     }
+
+    /// Gets the item at the given [BookmarkPath].
+    pub fn get(&self, path: &BookmarkPath) -> Option<&Item> {
+        self.get_at(&self.indexes_of(path)?)
+    }
+
+    /// Gets the item at the given [BookmarkPath] mutably.
+    pub fn get_mut(&mut self, path: &BookmarkPath) -> Option<&mut Item> {
+        let indexes = self.indexes_of(path)?;
+
+        self.get_at_mut(&indexes)
+    }
+
+    /// Resolves a [BookmarkPath] to the path of indexes of the item, see [Folder::move_at] for the paths.
+    pub fn indexes_of(&self, path: &BookmarkPath) -> Option<Vec<usize>> {
+        bookmark_path::resolve(&self.children, path)
+    }
+
+    /// Builds the [BookmarkPath] of the item at the given path of indexes, a separator has no path.
+    pub fn path_of(&self, indexes: &[usize]) -> Option<BookmarkPath> {
+        bookmark_path::path_of(&self.children, indexes)
+    }
+
+    /// Iterates over all the items with their [BookmarkPath], depth first and in the document order.
+    pub fn paths(&self) -> Paths<'_> {
+        Paths::new(&self.children)
+    }
+
+    /// Iterates over all the items of the folder as `(depth, parent path, &Item)`, see [Iter].
+    pub fn iter(&self) -> Iter<'_> {
+        Iter::new(&self.children)
+    }
+
+    /// Iterates mutably over all the items of the folder but the folders, see [IterMut].
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut::new(&mut self.children)
+    }
+
+    /// Visits all the items of the folder with a [Visitor].
+    pub fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        traverse::walk(&self.children, 0, &mut BookmarkPath::new(), visitor)
+    }
+
+    /// Visits and edits all the items of the folder with a [VisitorMut].
+    pub fn walk_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        traverse::walk_mut(&mut self.children, 0, &mut BookmarkPath::new(), visitor)
+    }
+
+    /// Gets the item at the given path of indexes, see [Folder::move_at] for the paths.
+    pub fn get_at(&self, path: &[usize]) -> Option<&Item> {
+        tree::get(&self.children, path)
+    }
+
+    /// Gets the item at the given path of indexes mutably.
+    pub fn get_at_mut(&mut self, path: &[usize]) -> Option<&mut Item> {
+        tree::get_mut(&mut self.children, path)
+    }
+
+    /// Inserts an item at the given path of indexes, the last index can be the length of the list to append the item.
+    pub fn insert_at(&mut self, path: &[usize], item: Item) -> Result<(), Error> {
+        tree::insert(&mut self.children, path, item)
+    }
+
+    /// Removes the item at the given path of indexes and returns it.
+    pub fn remove_at(&mut self, path: &[usize]) -> Result<Item, Error> {
+        tree::remove(&mut self.children, path)
+    }
+
+    /// Replaces the item at the given path of indexes and returns the previous one.
+    pub fn replace_at(&mut self, path: &[usize], item: Item) -> Result<Item, Error> {
+        tree::replace(&mut self.children, path, item)
+    }
+
+    /// Moves the item at the path `from` to the path `to`.
+    ///
+    /// A path is a list of indexes, each index selects a child of the current list and the next index
+    /// goes down into it : `&[1, 0]` is the first child of the second item, that must be a subfolder.
+    /// The path `to` is resolved once the item is removed, the items are left untouched on [Error::InvalidPath].
+    pub fn move_at(&mut self, from: &[usize], to: &[usize]) -> Result<(), Error> {
+        tree::move_item(&mut self.children, from, to)
+    }
+
+    /// Inserts an item at the given index of the children.
+    pub fn insert_child(&mut self, index: usize, item: Item) -> Result<(), Error> {
+        self.insert_at(&[index], item)
+    }
+
+    /// Removes the child at the given index and returns it.
+    pub fn remove_child(&mut self, index: usize) -> Result<Item, Error> {
+        self.remove_at(&[index])
+    }
+
+    /// Replaces the child at the given index and returns the previous one.
+    pub fn replace_child(&mut self, index: usize, item: Item) -> Result<Item, Error> {
+        self.replace_at(&[index], item)
+    }
+
+    /// Moves the child at the index `from` to the index `to` of the children.
+    pub fn move_child(&mut self, from: usize, to: usize) -> Result<(), Error> {
+        self.move_at(&[from], &[to])
+    }
 }
 
 impl PartialEq for Folder {
     fn eq(&self, other: &Self) -> bool {
         self.add_date == other.add_date
             && self.title == other.title
+            && self.description == other.description
+            && self.extra_attributes == other.extra_attributes
             && self.children == other.children
     }
 }
@@ -118,8 +257,11 @@ fn should_render_folder_html() {
         folded: true,
         personal_toolbar_folder: false,
         unfiled_bookmarks_folder: false,
-        last_modified: String::from("date"),
-        add_date: String::from("date"),
+        guid: String::new(),
+        description: String::new(),
+        extra_attributes: IndexMap::new(),
+        last_modified: Timestamp::from("date"),
+        add_date: Timestamp::from("date"),
         children: vec![],
     };
 
@@ -138,19 +280,62 @@ fn should_parse_netscape_empty_folder() {
     let h3 = parse_html().one(item).select_first("H3").unwrap();
 
     assert_eq!(
-        Folder::from_node(&h3.as_node()).unwrap(),
+        Folder::from_node(h3.as_node()).unwrap(),
         Folder {
             title: String::from("title"),
             folded: true,
             personal_toolbar_folder: false,
             unfiled_bookmarks_folder: false,
-            last_modified: String::from("date"),
-            add_date: String::from("date"),
+            guid: String::new(),
+            description: String::new(),
+            extra_attributes: IndexMap::new(),
+            last_modified: Timestamp::from("date"),
+            add_date: Timestamp::from("date"),
             children: vec![]
         }
     )
 }
 
+#[test]
+fn should_parse_netscape_folder_description() {
+    use kuchiki::parse_html;
+    use kuchiki::traits::TendrilSink;
+
+    let item = r#"
+    <DT><H3>title</H3>
+    <DD>multi-line
+    &lt;i&gt;description&lt;/i&gt;
+    <DL><p>
+        <DT><A HREF="url">name</A>
+    </DL><p>"#;
+    // the document owns the <DD/> sibling, it must outlive the parsing
+    let document = parse_html().one(item);
+    let dt = document.select_first("DT").unwrap();
+    let folder = Folder::from_node(dt.as_node()).unwrap();
+
+    assert_eq!(folder.description, "multi-line\n    <i>description</i>");
+    assert_eq!(folder.children.len(), 1);
+}
+
+#[test]
+fn should_roundtrip_folder_extra_attributes() {
+    use crate::parser::parse_html;
+    use kuchiki::traits::TendrilSink;
+
+    let item = r#"<DT><H3 ADD_DATE="date" ID="rdf:#$ZvPhC3" FEEDURL="https://example.com/feed">name</H3>
+<DL><p>
+</DL><p>"#;
+    let document = parse_html().one(item);
+    let h3 = document.select_first("H3").unwrap();
+    let folder = Folder::from_node(h3.as_node()).unwrap();
+
+    assert_eq!(
+        folder.extra_attributes.keys().collect::<Vec<_>>(),
+        vec!["ID", "FEEDURL"]
+    );
+    assert_eq!(folder.render().unwrap(), item);
+}
+
 #[test]
 fn should_parse_netscape_nested_folders() {
     use kuchiki::parse_html;
@@ -194,7 +379,7 @@ fn should_parse_netscape_nested_folders() {
     );
 
     assert_eq!(
-        Folder::from_node(&dt.as_node()).unwrap(),
+        Folder::from_node(dt.as_node()).unwrap(),
         FolderBuilder::default()
             .title("nested0")
             .children(vec![n1])
@@ -211,10 +396,46 @@ fn should_serialize_json_folder() {
         folded: false,
         personal_toolbar_folder: true,
         unfiled_bookmarks_folder: false,
-        add_date: String::from("date"),
-        last_modified: String::from("date"),
+        guid: String::new(),
+        description: String::new(),
+        extra_attributes: IndexMap::new(),
+        add_date: Timestamp::from("date"),
+        last_modified: Timestamp::from("date"),
         children: vec![],
     };
 
     assert_eq!(serde_json::to_string(&folder).unwrap(), json)
 }
+
+#[test]
+fn should_edit_folder_children() {
+    use crate::bookmark::BookmarkBuilder;
+
+    let bookmark = |name: &str| {
+        Item::Shortcut(
+            BookmarkBuilder::default()
+                .href(name)
+                .title(name)
+                .build()
+                .unwrap(),
+        )
+    };
+
+    let mut folder = FolderBuilder::default()
+        .children(vec![bookmark("a"), bookmark("b")])
+        .build()
+        .unwrap();
+
+    folder.insert_child(2, Item::Separator).unwrap();
+    folder.move_child(2, 0).unwrap();
+    folder.replace_child(1, bookmark("c")).unwrap();
+
+    assert_eq!(
+        folder.children,
+        vec![Item::Separator, bookmark("c"), bookmark("b")]
+    );
+    assert_eq!(folder.remove_child(2).unwrap(), bookmark("b"));
+    assert!(folder.remove_child(2).is_err());
+    assert!(folder.move_child(0, 3).is_err());
+    assert_eq!(folder.children.len(), 2);
+}
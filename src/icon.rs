@@ -0,0 +1,509 @@
+//! Contains the [Icon] decoding of the favicons of the bookmarks and their export to a directory.
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use indexmap::IndexMap;
+use md5::{Digest, Md5};
+use std::fs;
+use std::path::Path;
+
+use crate::item::Item;
+use crate::traverse::{Iter, IterMut};
+use crate::Bookmark;
+use crate::Error;
+
+/// The base64 engine of the data URIs, it accepts the encodings without padding.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// The image formats of the favicons, with their MIME type, their file extension and their signature.
+const FORMATS: [(&str, &str, &[u8]); 6] = [
+    ("image/png", "png", b"\x89PNG\r\n\x1a\n"),
+    ("image/gif", "gif", b"GIF8"),
+    ("image/jpeg", "jpg", b"\xff\xd8\xff"),
+    ("image/x-icon", "ico", b"\0\0\x01\0"),
+    ("image/bmp", "bmp", b"BM"),
+    ("image/webp", "webp", b"RIFF"),
+];
+
+/// The MIME type of the SVG images, they are recognized by their `<svg` tag.
+const SVG: (&str, &str) = ("image/svg+xml", "svg");
+
+/// A favicon decoded from a `data:image/...` URI.
+///
+/// ```rust
+/// use bookmarkt::Icon;
+///
+/// let icon = Icon::from_data_uri("data:image/gif;base64,R0lGODlhAQABAAAAACw=").unwrap();
+///
+/// assert_eq!(icon.mime, "image/gif");
+/// assert_eq!(icon.extension(), Some("gif"));
+/// assert!(icon.validate().is_ok());
+/// assert_eq!(icon.to_data_uri(), "data:image/gif;base64,R0lGODlhAQABAAAAACw=");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Icon {
+    /// The `mime` attribute stores the MIME type of the image, like `image/png`.
+    pub mime: String,
+
+    /// The `data` attribute stores the raw bytes of the image.
+    pub data: Vec<u8>,
+}
+
+impl Icon {
+    /// Decodes a data URI, the base64 and the percent encodings are supported.
+    ///
+    /// It fails with [Error::InvalidIcon] when the URI isn't a data URI of an image.
+    pub fn from_data_uri(uri: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidIcon(reason.to_string());
+
+        let uri = uri.trim();
+        let content = uri
+            .get(..5)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("data:"))
+            .map(|_| &uri[5..])
+            .ok_or_else(|| invalid("not a data URI"))?;
+        let (header, payload) = content
+            .split_once(',')
+            .ok_or_else(|| invalid("missing data"))?;
+
+        let mut parameters = header.split(';');
+        let mime = parameters.next().unwrap_or_default().trim().to_lowercase();
+        let is_base64 = parameters.any(|parameter| parameter.trim().eq_ignore_ascii_case("base64"));
+
+        if !mime.starts_with("image/") {
+            return Err(invalid("not an image"));
+        }
+
+        let data = if is_base64 {
+            let payload: String = percent_decode(payload)
+                .into_iter()
+                .filter(|byte| !byte.is_ascii_whitespace())
+                .map(char::from)
+                .collect();
+
+            BASE64
+                .decode(payload)
+                .map_err(|_| invalid("malformed base64 data"))?
+        } else {
+            percent_decode(payload)
+        };
+
+        Ok(Icon { mime, data })
+    }
+
+    /// Encodes the icon as a base64 data URI.
+    pub fn to_data_uri(&self) -> String {
+        format!("data:{};base64,{}", self.mime, BASE64.encode(&self.data))
+    }
+
+    /// Gets the md5 hash of the image, in hexadecimal, the identical icons have the same hash.
+    pub fn hash(&self) -> String {
+        Md5::digest(&self.data)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Gets the file extension of the image from its MIME type, it is [None] for the unknown formats.
+    pub fn extension(&self) -> Option<&'static str> {
+        let mime = canonical_mime(&self.mime);
+
+        FORMATS
+            .iter()
+            .map(|(format, extension, _)| (*format, *extension))
+            .chain([SVG])
+            .find(|(format, _)| *format == mime)
+            .map(|(_, extension)| extension)
+    }
+
+    /// Gets the name of the exported file of the icon, it is its hash with its extension.
+    pub fn file_name(&self) -> String {
+        format!("{}.{}", self.hash(), self.extension().unwrap_or("bin"))
+    }
+
+    /// Checks that the data is an image of the declared MIME type.
+    ///
+    /// It fails with [Error::InvalidIcon] when the image is empty, of an unknown format or
+    /// of another format than the declared one.
+    pub fn validate(&self) -> Result<(), Error> {
+        let detected = detect_mime(&self.data)
+            .ok_or_else(|| Error::InvalidIcon(String::from("unknown image format")))?;
+
+        if detected != canonical_mime(&self.mime) {
+            return Err(Error::InvalidIcon(format!(
+                "{} declared but {} found",
+                self.mime, detected
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the declared MIME type by the detected one, the mislabeled icons are common :
+    /// Firefox exports its SVG icons as `image/png`.
+    pub(crate) fn corrected(self) -> Result<Self, Error> {
+        let detected = detect_mime(&self.data)
+            .ok_or_else(|| Error::InvalidIcon(String::from("unknown image format")))?;
+
+        Ok(Icon {
+            mime: detected.to_string(),
+            ..self
+        })
+    }
+
+    /// Reads an exported icon, its MIME type is given by the extension of the file.
+    fn from_file(path: &Path) -> Result<Self, Error> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        let mime = FORMATS
+            .iter()
+            .map(|(format, extension, _)| (*format, *extension))
+            .chain([SVG])
+            .find(|(_, known)| *known == extension)
+            .map(|(format, _)| format)
+            .ok_or_else(|| Error::InvalidIcon(format!("unknown file extension {:?}", extension)))?;
+
+        Ok(Icon {
+            mime: mime.to_string(),
+            data: fs::read(path)?,
+        })
+    }
+}
+
+/// The deduplicated icons of a document, see [Netscape::icons].
+///
+/// [Netscape::icons]: crate::Netscape::icons
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IconSet {
+    /// The `icons` [IndexMap] stores the distinct valid icons by their [Icon::file_name], in the document order.
+    /// Their MIME type is detected from their content, whatever they declare.
+    pub icons: IndexMap<String, Icon>,
+
+    /// The `bookmarks` [IndexMap] stores the `href` of the bookmarks using each icon, by its [Icon::file_name].
+    pub bookmarks: IndexMap<String, Vec<String>>,
+
+    /// The `invalid` [Vec] stores the `href` of the bookmarks with an icon that can't be decoded or validated.
+    pub invalid: Vec<String>,
+}
+
+impl IconSet {
+    /// Records the data URIs of a bookmark, it gives the file names of its valid icons.
+    fn record(&mut self, bookmark: &Bookmark) -> [Option<String>; 2] {
+        [&bookmark.icon, &bookmark.icon_uri].map(|uri| {
+            if !is_data_uri(uri) {
+                return None;
+            }
+
+            match Icon::from_data_uri(uri).and_then(Icon::corrected) {
+                Ok(icon) => {
+                    let file_name = icon.file_name();
+
+                    self.icons.entry(file_name.clone()).or_insert(icon);
+                    self.bookmarks
+                        .entry(file_name.clone())
+                        .or_default()
+                        .push(bookmark.href.clone());
+
+                    Some(file_name)
+                }
+                Err(_) => {
+                    self.invalid.push(bookmark.href.clone());
+                    None
+                }
+            }
+        })
+    }
+}
+
+/// Collects the icons of the bookmarks, the `icon` and the `icon_uri` holding a data URI are decoded.
+pub(crate) fn collect(children: &[Item]) -> IconSet {
+    let mut set = IconSet::default();
+
+    for (_, _, item) in Iter::new(children) {
+        if let Item::Shortcut(bookmark) = item {
+            set.record(bookmark);
+        }
+    }
+
+    set
+}
+
+/// Writes the icons of the bookmarks to a directory and replaces their data URIs by the paths of the
+/// exported files, the directory joined with their [Icon::file_name].
+///
+/// The files are all written before any attribute is replaced, so the document is unchanged when a
+/// write fails. The invalid icons are kept inline.
+pub(crate) fn export(children: &mut [Item], dir: &Path) -> Result<IconSet, Error> {
+    let mut set = IconSet::default();
+    let mut exported = vec![];
+
+    for (_, _, item) in Iter::new(children) {
+        if let Item::Shortcut(bookmark) = item {
+            exported.push(set.record(bookmark));
+        }
+    }
+
+    fs::create_dir_all(dir)?;
+
+    for (file_name, icon) in set.icons.iter() {
+        fs::write(dir.join(file_name), &icon.data)?;
+    }
+
+    let path = |file_name: String| dir.join(file_name).to_string_lossy().into_owned();
+    let bookmarks = IterMut::new(children).filter_map(|(_, _, item)| match item {
+        Item::Shortcut(bookmark) => Some(bookmark),
+        _ => None,
+    });
+
+    for (bookmark, [icon, icon_uri]) in bookmarks.zip(exported) {
+        if let Some(file_name) = icon {
+            bookmark.icon = path(file_name);
+        }
+
+        if let Some(file_name) = icon_uri {
+            bookmark.icon_uri = path(file_name);
+        }
+    }
+
+    Ok(set)
+}
+
+/// Replaces the paths written by [export] by the data URIs of the files, it gives the count of
+/// replaced attributes.
+///
+/// An attribute is replaced when it is a file of the directory : the directory joined with a file name,
+/// or a bare file name. The other attributes are kept.
+pub(crate) fn inline(children: &mut [Item], dir: &Path) -> Result<usize, Error> {
+    let mut count = 0;
+
+    for (_, _, item) in IterMut::new(children) {
+        if let Item::Shortcut(bookmark) = item {
+            for attribute in [&mut bookmark.icon, &mut bookmark.icon_uri] {
+                if let Some(file) = exported_file(attribute, dir) {
+                    *attribute = Icon::from_file(&file)?.to_data_uri();
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+/// Gets the file of the directory an attribute points at, if any.
+fn exported_file(attribute: &str, dir: &Path) -> Option<std::path::PathBuf> {
+    if attribute.is_empty() || is_data_uri(attribute) {
+        return None;
+    }
+
+    let path = Path::new(attribute);
+    let parent = path.parent()?;
+    let file = dir.join(path.file_name()?);
+
+    ((parent.as_os_str().is_empty() || parent == dir) && file.is_file()).then_some(file)
+}
+
+/// Checks if an attribute holds a data URI.
+pub(crate) fn is_data_uri(uri: &str) -> bool {
+    uri.trim()
+        .get(..5)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+}
+
+/// Gets the usual MIME type of the aliased ones, like `image/jpg` for `image/jpeg`.
+fn canonical_mime(mime: &str) -> &str {
+    match mime {
+        "image/jpg" | "image/pjpeg" => "image/jpeg",
+        "image/vnd.microsoft.icon" | "image/ico" | "image/icon" => "image/x-icon",
+        "image/x-ms-bmp" => "image/bmp",
+        "image/svg" => "image/svg+xml",
+        mime => mime,
+    }
+}
+
+/// Detects the MIME type of an image from its signature.
+fn detect_mime(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"RIFF") && data.get(8..12) != Some(b"WEBP") {
+        return None;
+    }
+
+    if let Some((mime, _, _)) = FORMATS
+        .iter()
+        .find(|(_, _, signature)| data.starts_with(signature))
+    {
+        return Some(mime);
+    }
+
+    let text = String::from_utf8_lossy(&data[..data.len().min(1024)]).to_lowercase();
+
+    text.contains("<svg").then_some(SVG.0)
+}
+
+/// Decodes the `%XX` sequences of a data URI, the malformed ones are kept.
+fn percent_decode(raw: &str) -> Vec<u8> {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut position = 0;
+
+    while position < bytes.len() {
+        let escaped = bytes
+            .get(position + 1..position + 3)
+            .filter(|_| bytes[position] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                position += 3;
+            }
+            None => {
+                decoded.push(bytes[position]);
+                position += 1;
+            }
+        }
+    }
+
+    decoded
+}
+
+#[allow(dead_code)]
+const PNG: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==";
+
+#[allow(dead_code)]
+fn mock_bookmark(href: &str, icon: &str) -> Item {
+    Item::Shortcut(Bookmark {
+        href: href.to_string(),
+        title: href.to_string(),
+        icon: icon.to_string(),
+        ..Bookmark::default()
+    })
+}
+
+#[test]
+fn should_decode_data_uri() {
+    let icon = Icon::from_data_uri(PNG).unwrap();
+
+    assert_eq!(icon.mime, "image/png");
+    assert!(icon.data.starts_with(b"\x89PNG"));
+    assert!(icon.validate().is_ok());
+    assert_eq!(icon.to_data_uri(), PNG);
+    assert_eq!(icon.file_name(), format!("{}.png", icon.hash()));
+
+    let svg = Icon::from_data_uri("DATA:image/svg+xml,%3Csvg%20xmlns='x'/%3E").unwrap();
+
+    assert_eq!(svg.data, b"<svg xmlns='x'/>");
+    assert_eq!(svg.extension(), Some("svg"));
+    assert!(svg.validate().is_ok());
+}
+
+#[test]
+fn should_reject_invalid_icons() {
+    let invalid = |uri: &str| matches!(Icon::from_data_uri(uri), Err(Error::InvalidIcon(_)));
+
+    assert!(invalid("https://example.com/favicon.ico"));
+    assert!(invalid("data:text/plain;base64,aGVsbG8="));
+    assert!(invalid("data:image/png;base64,!!!"));
+    assert!(invalid("data:image/png;base64"));
+
+    let mislabeled = Icon {
+        mime: String::from("image/gif"),
+        ..Icon::from_data_uri(PNG).unwrap()
+    };
+
+    assert!(matches!(mislabeled.validate(), Err(Error::InvalidIcon(_))));
+    assert!(Icon::from_data_uri("data:image/png;base64,aGVsbG8=")
+        .unwrap()
+        .validate()
+        .is_err());
+}
+
+#[test]
+fn should_collect_deduplicated_icons() {
+    let children = vec![
+        mock_bookmark("a", PNG),
+        mock_bookmark("b", "data:image/png;base64,aGVsbG8="),
+        mock_bookmark("c", "https://example.com/favicon.ico"),
+        mock_bookmark("d", PNG),
+    ];
+    let mislabeled = mock_bookmark("e", "data:image/png;base64,PHN2Zz48L3N2Zz4=");
+    let set = collect(&[children, vec![mislabeled]].concat());
+    let file_name = Icon::from_data_uri(PNG).unwrap().file_name();
+
+    assert_eq!(set.icons.len(), 2);
+    assert_eq!(set.icons[1].mime, "image/svg+xml");
+    assert!(set.icons.keys().nth(1).unwrap().ends_with(".svg"));
+    assert_eq!(set.bookmarks[&file_name], vec!["a", "d"]);
+    assert_eq!(set.invalid, vec!["b"]);
+}
+
+#[test]
+fn should_export_and_inline_icons() {
+    let dir = std::env::temp_dir().join(format!("bookmarkt-icons-{}", std::process::id()));
+    let mut children = vec![
+        mock_bookmark("a", PNG),
+        mock_bookmark("b", "data:image/png;base64,aGVsbG8="),
+        mock_bookmark("c", PNG),
+    ];
+    let file_name = Icon::from_data_uri(PNG).unwrap().file_name();
+
+    let set = export(&mut children, &dir).unwrap();
+    let icons = children
+        .iter()
+        .filter_map(|item| item.take_shortcut().map(|bookmark| bookmark.icon.clone()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(set.icons.len(), 1);
+    assert_eq!(icons[0], dir.join(&file_name).to_string_lossy());
+    assert_eq!(icons[1], "data:image/png;base64,aGVsbG8=");
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+    assert_eq!(inline(&mut children, &dir).unwrap(), 2);
+    assert_eq!(children[0], mock_bookmark("a", PNG));
+    assert_eq!(children[2], mock_bookmark("c", PNG));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_keep_document_when_export_fails() {
+    let dir = std::env::temp_dir().join(format!("bookmarkt-failing-icons-{}", std::process::id()));
+    let children = vec![mock_bookmark("a", PNG), mock_bookmark("b", PNG)];
+    let file_name = Icon::from_data_uri(PNG).unwrap().file_name();
+
+    // a directory in place of the icon file makes the write fail
+    fs::create_dir_all(dir.join(&file_name)).unwrap();
+
+    let mut exported = children.clone();
+
+    assert!(matches!(export(&mut exported, &dir), Err(Error::Io(_))));
+    assert_eq!(exported, children);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_inline_bare_file_names() {
+    let dir = std::env::temp_dir().join(format!("bookmarkt-bare-icons-{}", std::process::id()));
+    let icon = Icon::from_data_uri(PNG).unwrap();
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(icon.file_name()), &icon.data).unwrap();
+
+    let mut children = vec![
+        mock_bookmark("a", &icon.file_name()),
+        mock_bookmark("b", &format!("elsewhere/{}", icon.file_name())),
+        mock_bookmark("c", "https://example.com/favicon.ico"),
+    ];
+
+    assert_eq!(inline(&mut children, &dir).unwrap(), 1);
+    assert_eq!(children[0], mock_bookmark("a", PNG));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,17 +1,21 @@
 //! Contains the [Item] enum that dispatches all the implementations of the items.
 use kuchiki::NodeRef;
-use serde::Serialize;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::node_ref_ext::*;
 use crate::Bookmark;
+use crate::Feed;
 use crate::Folder;
+use crate::WebSlice;
 
 /// Represents all available item types of the Microsoft's Netscape Bookmark File format.
 ///
-/// * TODO The `feed` item should represent a RSS feed.
-/// * TODO The `web slice` item represents a legacy [Web Slice] object.
-///
-/// [Web Slice]: https://docs.microsoft.com/en-us/previous-versions/windows/desktop/cc956158(v=vs.85)
-#[derive(Clone, Debug, Serialize)]
+/// The JSON representation is untagged : a subfolder is told apart from a shortcut by its
+/// `children` field, a feed by its `feed_url` field and a web slice by its `is_live_preview` field,
+/// the models reject the fields of the other ones.
+/// A separator is represented by the `{"separator":true}` object.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Item {
     /// The `Subfolder` item is implemented by the [Folder] struct.
@@ -19,49 +23,177 @@ pub enum Item {
 
     /// The `Shortcut` item is represented by a [Bookmark].
     Shortcut(Bookmark),
+
+    /// The `Feed` item is a RSS or Atom feed represented by a [Feed].
+    Feed(Feed),
+
+    /// The `WebSlice` item is a legacy Internet Explorer's web slice represented by a [WebSlice].
+    WebSlice(WebSlice),
+
+    /// The `Separator` item is a horizontal rule (`<HR>`) between two items of a list.
+    #[serde(
+        serialize_with = "serialize_separator",
+        deserialize_with = "deserialize_separator"
+    )]
+    Separator,
+}
+
+/// Represents the JSON object of a separator.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SeparatorMarker {
+    separator: bool,
+}
+
+fn serialize_separator<S: Serializer>(serializer: S) -> Result<S::Ok, S::Error> {
+    SeparatorMarker { separator: true }.serialize(serializer)
+}
+
+fn deserialize_separator<'de, D: Deserializer<'de>>(deserializer: D) -> Result<(), D::Error> {
+    if SeparatorMarker::deserialize(deserializer)?.separator {
+        Ok(())
+    } else {
+        Err(D::Error::custom("a separator must be flagged as true"))
+    }
+}
+
+/// Collects the items of a `<DL>` list in the **same** order than the document.
+///
+/// The HTML parser nests the `<HR>` separators in the preceding `<DT>` or `<DD>` elements,
+/// so they are looked up in these elements too.
+pub(crate) fn collect_items(list: &NodeRef) -> Vec<Item> {
+    let mut items = vec![];
+
+    for child in list.children() {
+        if child.is_element("DT") || child.is_element("DD") {
+            items.extend(Item::from_node(&child));
+            items.extend(
+                child
+                    .children()
+                    .filter(|n| n.is_element("HR"))
+                    .map(|_| Item::Separator),
+            );
+        } else if child.is_element("HR") {
+            items.push(Item::Separator);
+        }
+    }
+
+    items
 }
 
 impl Item {
-    /// Creates a [Folder] or a [Bookmark] models from any given item.
+    /// Creates a [Folder], a [Bookmark], a [Feed], a [WebSlice] or a separator models from any given item.
     pub fn from_node(node: &NodeRef) -> Option<Self> {
-        if let Some(bookmark) = Bookmark::from_node(node) {
+        if node.is_element("HR") {
+            Some(Item::Separator)
+        } else if let Some(feed) = Feed::from_node(node) {
+            Some(Item::Feed(feed))
+        } else if let Some(web_slice) = WebSlice::from_node(node) {
+            Some(Item::WebSlice(web_slice))
+        } else if let Some(bookmark) = Bookmark::from_node(node) {
             Some(Item::Shortcut(bookmark))
-        } else if let Some(folder) = Folder::from_node(node) {
-            Some(Item::Subfolder(folder))
         } else {
-            None
+            Folder::from_node(node).map(Item::Subfolder)
         }
     }
 
-    /// Checks if the item is a shortcut
-    pub fn is_shortcut(&self) -> bool {
+    /// Gets the title of the item, a separator has no title
+    pub fn title(&self) -> Option<&str> {
         match self {
-            Item::Subfolder(_) => false,
-            Item::Shortcut(_) => true,
+            Item::Subfolder(folder) => Some(&folder.title),
+            Item::Shortcut(bookmark) => Some(&bookmark.title),
+            Item::Feed(feed) => Some(&feed.title),
+            Item::WebSlice(web_slice) => Some(&web_slice.title),
+            Item::Separator => None,
         }
     }
 
+    /// Checks if the item is a shortcut
+    pub fn is_shortcut(&self) -> bool {
+        matches!(self, Item::Shortcut(_))
+    }
+
     /// Takes a [Bookmark] out of the shortcut item
     pub fn take_shortcut(&self) -> Option<&Bookmark> {
         match self {
-            Item::Subfolder(_) => None,
             Item::Shortcut(bookmark) => Some(bookmark),
+            _ => None,
         }
     }
 
-    /// Checks if the item is a subfolder
-    pub fn is_subfolder(&self) -> bool {
+    /// Borrows the [Bookmark] of the shortcut item mutably
+    pub fn as_shortcut_mut(&mut self) -> Option<&mut Bookmark> {
+        match self {
+            Item::Shortcut(bookmark) => Some(bookmark),
+            _ => None,
+        }
+    }
+
+    /// Converts the shortcut item into its [Bookmark]
+    pub fn into_shortcut(self) -> Option<Bookmark> {
+        match self {
+            Item::Shortcut(bookmark) => Some(bookmark),
+            _ => None,
+        }
+    }
+
+    /// Checks if the item is a feed
+    pub fn is_feed(&self) -> bool {
+        matches!(self, Item::Feed(_))
+    }
+
+    /// Takes a [Feed] out of the feed item
+    pub fn take_feed(&self) -> Option<&Feed> {
         match self {
-            Item::Subfolder(_) => true,
-            Item::Shortcut(_) => false,
+            Item::Feed(feed) => Some(feed),
+            _ => None,
         }
     }
 
+    /// Checks if the item is a web slice
+    pub fn is_web_slice(&self) -> bool {
+        matches!(self, Item::WebSlice(_))
+    }
+
+    /// Takes a [WebSlice] out of the web slice item
+    pub fn take_web_slice(&self) -> Option<&WebSlice> {
+        match self {
+            Item::WebSlice(web_slice) => Some(web_slice),
+            _ => None,
+        }
+    }
+
+    /// Checks if the item is a subfolder
+    pub fn is_subfolder(&self) -> bool {
+        matches!(self, Item::Subfolder(_))
+    }
+
+    /// Checks if the item is a separator
+    pub fn is_separator(&self) -> bool {
+        matches!(self, Item::Separator)
+    }
+
     /// Takes a [Folder] out of the subfolder item
     pub fn take_subfolder(&self) -> Option<&Folder> {
         match self {
             Item::Subfolder(folder) => Some(folder),
-            Item::Shortcut(_) => None,
+            _ => None,
+        }
+    }
+
+    /// Borrows the [Folder] of the subfolder item mutably
+    pub fn as_subfolder_mut(&mut self) -> Option<&mut Folder> {
+        match self {
+            Item::Subfolder(folder) => Some(folder),
+            _ => None,
+        }
+    }
+
+    /// Converts the subfolder item into its [Folder]
+    pub fn into_subfolder(self) -> Option<Folder> {
+        match self {
+            Item::Subfolder(folder) => Some(folder),
+            _ => None,
         }
     }
 }
@@ -71,7 +203,112 @@ impl PartialEq for Item {
         match (self, other) {
             (Item::Subfolder(f1), Item::Subfolder(f2)) => f1 == f2,
             (Item::Shortcut(b1), Item::Shortcut(b2)) => b1 == b2,
+            (Item::Feed(f1), Item::Feed(f2)) => f1 == f2,
+            (Item::WebSlice(w1), Item::WebSlice(w2)) => w1 == w2,
+            (Item::Separator, Item::Separator) => true,
             _ => false,
         }
     }
 }
+
+#[test]
+fn should_parse_separators() {
+    use kuchiki::parse_html;
+    use kuchiki::traits::TendrilSink;
+
+    let list = r#"
+    <DL><p>
+        <DT><A HREF="a">a</A>
+        <HR>
+        <DT><H3>f</H3>
+        <DL><p>
+        </DL><p>
+        <HR>
+        <HR>
+        <DT><A HREF="b">b</A>
+        <DT><A HREF="c" FEED="true" FEEDURL="c/feed">c</A>
+        <DT><A HREF="d" WEBSLICE="true">d</A>
+    </DL><p>"#;
+    let dl = parse_html().one(list).select_first("DL").unwrap();
+
+    let kinds = collect_items(dl.as_node())
+        .iter()
+        .map(|item| match item {
+            Item::Subfolder(_) => "folder",
+            Item::Shortcut(_) => "bookmark",
+            Item::Feed(_) => "feed",
+            Item::WebSlice(_) => "web slice",
+            Item::Separator => "separator",
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        kinds,
+        vec![
+            "bookmark",
+            "separator",
+            "folder",
+            "separator",
+            "separator",
+            "bookmark",
+            "feed",
+            "web slice"
+        ]
+    );
+}
+
+#[test]
+fn should_serialize_json_feed_item() {
+    let json = r#"[{"href":"url","title":"name","add_date":"","last_visit":"","last_modified":"","icon_uri":"","icon":""},{"feed_url":"url/feed","title":"feed","add_date":"","last_modified":""}]"#;
+    let items: Vec<Item> = serde_json::from_str(json).unwrap();
+
+    assert!(items[0].is_shortcut());
+    assert_eq!(items[1].take_feed().unwrap().feed_url, "url/feed");
+    assert_eq!(serde_json::to_string(&items).unwrap(), json);
+}
+
+#[test]
+fn should_serialize_json_web_slice_item() {
+    let json = r#"[{"href":"url","title":"name","add_date":"","last_visit":"","last_modified":"","is_live_preview":false}]"#;
+    let items: Vec<Item> = serde_json::from_str(json).unwrap();
+
+    assert!(items[0].is_web_slice());
+    assert_eq!(serde_json::to_string(&items).unwrap(), json);
+}
+
+#[test]
+fn should_serialize_json_separator() {
+    let json = r#"[{"separator":true}]"#;
+    let items: Vec<Item> = serde_json::from_str(json).unwrap();
+
+    assert!(items[0].is_separator());
+    assert_eq!(serde_json::to_string(&items).unwrap(), json);
+    assert!(serde_json::from_str::<Item>(r#"{"separator":false}"#).is_err());
+}
+
+#[test]
+fn should_access_items_mutably() {
+    use crate::bookmark::BookmarkBuilder;
+    use crate::folder::FolderBuilder;
+
+    let bookmark = BookmarkBuilder::default()
+        .href("url")
+        .title("name")
+        .build()
+        .unwrap();
+    let mut shortcut = Item::Shortcut(bookmark);
+    let mut subfolder = Item::Subfolder(FolderBuilder::default().build().unwrap());
+
+    shortcut.as_shortcut_mut().unwrap().title = String::from("renamed");
+    subfolder
+        .as_subfolder_mut()
+        .unwrap()
+        .children
+        .push(shortcut.clone());
+
+    assert!(shortcut.as_subfolder_mut().is_none());
+    assert!(subfolder.as_shortcut_mut().is_none());
+    assert_eq!(shortcut.into_shortcut().unwrap().title, "renamed");
+    assert_eq!(subfolder.into_subfolder().unwrap().children.len(), 1);
+    assert!(Item::Separator.into_shortcut().is_none());
+}
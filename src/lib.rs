@@ -55,15 +55,51 @@
 #[macro_use]
 extern crate derive_builder;
 
+mod bookmark_path;
+mod chromium;
 mod collection;
+mod dedupe;
+mod diff;
+mod encoding;
+mod escape;
+mod firefox;
+mod icon;
 mod item;
+mod lint;
+mod merge;
 mod node_ref_ext;
+mod parser;
+mod stream;
+mod sync;
+mod traverse;
+mod tree;
+mod writer;
 
 mod bookmark;
+mod error;
+mod feed;
 mod folder;
 mod netscape;
+mod timestamp;
+mod web_slice;
+
+pub mod url;
 
 pub use bookmark::Bookmark;
+pub use bookmark_path::{BookmarkPath, Paths, Segment};
+pub use dedupe::{DedupePolicy, DedupeReport, DedupeStrategy, RemovedBookmark};
+pub use diff::{Change, Diff};
+pub use error::Error;
+pub use feed::Feed;
 pub use folder::Folder;
+pub use icon::{Icon, IconSet};
 pub use item::Item;
-pub use netscape::Netscape;
+pub use lint::{Diagnostic, Rule, Severity};
+pub use merge::{DateConflict, MergeOptions, MergeSummary, MergedItem, TitleConflict};
+pub use netscape::{Netscape, ParseMode};
+pub use stream::{Event, Events};
+pub use sync::{Conflict, SyncReport, Unapplied};
+pub use timestamp::{Precision, Timestamp};
+pub use traverse::{Iter, IterMut, Visitor, VisitorMut};
+pub use web_slice::WebSlice;
+pub use writer::HtmlWriter;
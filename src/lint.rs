@@ -0,0 +1,516 @@
+//! Contains the validator of the raw documents, it reports the [Diagnostic]s the lenient parser ignores.
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::states::RawKind;
+use html5ever::tokenizer::{
+    BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use std::fmt;
+
+use crate::icon::{self, Icon};
+use crate::Timestamp;
+
+/// Tells how serious a [Diagnostic] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The document is readable, but the data is probably wrong.
+    Warning,
+
+    /// The document breaks the specification, the lenient parser drops or guesses some data.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Identifies the check that reported a [Diagnostic].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// A bookmark has no `HREF` or an empty one, or a feed has no `FEEDURL` : its `HREF` is optional.
+    EmptyHref,
+
+    /// An `ADD_DATE`, `LAST_VISIT` or `LAST_MODIFIED` attribute isn't a UNIX time.
+    InvalidDate,
+
+    /// The `LAST_MODIFIED` date of an item is earlier than its `ADD_DATE`.
+    ModifiedBeforeAdded,
+
+    /// A `<DL>` list is never closed.
+    UnclosedList,
+
+    /// A `</DL>` closes no list.
+    UnexpectedListEnd,
+
+    /// A `<DT>` element holds neither an `<A>` nor an `<H3>`.
+    MalformedItem,
+
+    /// More than one folder is the `PERSONAL_TOOLBAR_FOLDER`.
+    DuplicateToolbarFolder,
+
+    /// An `ICON` or `ICON_URI` data URI isn't a valid image.
+    InvalidIcon,
+}
+
+impl Rule {
+    /// Gets the [Severity] of the diagnostics of the rule.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Rule::ModifiedBeforeAdded => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+/// A problem of a raw document, located by its line and its column, both counted from `1`.
+///
+/// ```rust
+/// use bookmarkt::{Netscape, Rule};
+///
+/// let html = "<!DOCTYPE NETSCAPE-Bookmark-file-1>
+/// <DL><p>
+///     <DT><A HREF=\"\" ADD_DATE=\"yesterday\">Nowhere</A>
+/// </DL>";
+/// let diagnostics = Netscape::lint(html);
+///
+/// assert_eq!(diagnostics[0].rule, Rule::EmptyHref);
+/// assert_eq!((diagnostics[0].line, diagnostics[0].column), (3, 9));
+/// assert_eq!(diagnostics[1].to_string(), "3:9: error: invalid ADD_DATE \"yesterday\"");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    /// The `rule` attribute identifies the failed check.
+    pub rule: Rule,
+
+    /// The `severity` attribute tells how serious the problem is, see [Rule::severity].
+    pub severity: Severity,
+
+    /// The `line` attribute stores the line of the tag with the problem.
+    pub line: usize,
+
+    /// The `column` attribute stores the column of the `<` of the tag, in characters.
+    pub column: usize,
+
+    /// The `message` attribute describes the problem.
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.line, self.column, self.severity, self.message
+        )
+    }
+}
+
+/// Finds the offsets of the tags emitted by the tokenizer in the raw document.
+///
+/// The tokenizer only tells the lines, so the tags are searched in the document, in the same order.
+/// The search starts after the end of the previous tag, so the markup in its attribute values is skipped.
+struct Locator<'a> {
+    raw: &'a str,
+    /// The document in lowercase, with the same offsets.
+    lowercase: Vec<u8>,
+    /// The offset after the last found tag.
+    cursor: usize,
+    /// The offsets of the starts of the lines.
+    lines: Vec<usize>,
+}
+
+impl<'a> Locator<'a> {
+    fn new(raw: &'a str) -> Self {
+        let lines = std::iter::once(0)
+            .chain(raw.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+
+        Locator {
+            raw,
+            lowercase: raw.as_bytes().to_ascii_lowercase(),
+            cursor: 0,
+            lines,
+        }
+    }
+
+    /// Finds the next occurrence of a tag, like `<dl` or `</dl`, that isn't the prefix of another name.
+    fn find_tag(&mut self, tag: &Tag) -> usize {
+        let prefix = match tag.kind {
+            TagKind::StartTag => format!("<{}", tag.name),
+            TagKind::EndTag => format!("</{}", tag.name),
+        };
+
+        let found = self.find(prefix.as_bytes(), |next| {
+            next.is_none_or(|b| b == b'>' || b == b'/' || b.is_ascii_whitespace())
+        });
+
+        match found {
+            Some(offset) => {
+                self.cursor = self.tag_end(offset + prefix.len());
+                offset
+            }
+            None => self.cursor,
+        }
+    }
+
+    /// Finds the offset after the `>` ending a tag, the quoted attribute values are skipped since
+    /// they can hold markup, like the `javascript:` urls of the bookmarklets.
+    fn tag_end(&self, from: usize) -> usize {
+        let mut offset = from;
+        let mut after_equal = false;
+
+        while let Some(&b) = self.lowercase.get(offset) {
+            match b {
+                b'>' => return offset + 1,
+                b'"' | b'\'' if after_equal => {
+                    offset = self.lowercase[offset + 1..]
+                        .iter()
+                        .position(|&c| c == b)
+                        .map_or(self.lowercase.len(), |end| offset + 1 + end);
+                    after_equal = false;
+                }
+                b'=' => after_equal = true,
+                b if b.is_ascii_whitespace() => (),
+                _ => after_equal = false,
+            }
+
+            offset += 1;
+        }
+
+        offset
+    }
+
+    /// Skips the next comment, its content isn't tokenized as tags.
+    fn skip_comment(&mut self) {
+        if let Some(start) = self.find(b"<!--", |_| true) {
+            self.cursor = self
+                .find_from(start + 4, b"-->", |_| true)
+                .map_or(self.lowercase.len(), |end| end + 3);
+        }
+    }
+
+    fn find(&self, needle: &[u8], accept: impl Fn(Option<u8>) -> bool) -> Option<usize> {
+        self.find_from(self.cursor, needle, accept)
+    }
+
+    fn find_from(
+        &self,
+        from: usize,
+        needle: &[u8],
+        accept: impl Fn(Option<u8>) -> bool,
+    ) -> Option<usize> {
+        (from..self.lowercase.len()).find(|&offset| {
+            self.lowercase[offset..].starts_with(needle)
+                && accept(self.lowercase.get(offset + needle.len()).copied())
+        })
+    }
+
+    /// Converts an offset to a line and a column, both counted from `1`.
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.lines.partition_point(|&start| start <= offset);
+        let start = self.lines[line - 1];
+        let column = self
+            .raw
+            .get(start..offset)
+            .map_or(1, |text| text.chars().count() + 1);
+
+        (line, column)
+    }
+}
+
+/// Checks the tags of the document as they are tokenized.
+struct LintSink<'a> {
+    locator: Locator<'a>,
+    diagnostics: Vec<Diagnostic>,
+    /// The offsets of the open `<DL>` lists.
+    lists: Vec<usize>,
+    /// The offset of the `<DT>` waiting for its `<A>` or `<H3>`.
+    term: Option<usize>,
+    /// The offset of the first `PERSONAL_TOOLBAR_FOLDER`.
+    toolbar: Option<usize>,
+}
+
+impl<'a> LintSink<'a> {
+    fn report(&mut self, offset: usize, rule: Rule, message: String) {
+        let (line, column) = self.locator.position(offset);
+
+        self.diagnostics.push(Diagnostic {
+            rule,
+            severity: rule.severity(),
+            line,
+            column,
+            message,
+        });
+    }
+
+    /// Reports the pending `<DT>`, it isn't followed by an `<A>` or an `<H3>`.
+    fn report_term(&mut self) {
+        if let Some(offset) = self.term.take() {
+            self.report(
+                offset,
+                Rule::MalformedItem,
+                String::from("<DT> without <A> or <H3>"),
+            );
+        }
+    }
+
+    fn process_tag(&mut self, tag: Tag) -> TokenSinkResult<()> {
+        let offset = self.locator.find_tag(&tag);
+
+        if tag.kind == TagKind::StartTag {
+            if matches!(&*tag.name, "a" | "h3") {
+                self.term = None;
+            } else {
+                self.report_term();
+            }
+        }
+
+        match (tag.kind, &*tag.name) {
+            (TagKind::StartTag, "title") => return TokenSinkResult::RawData(RawKind::Rcdata),
+            (TagKind::StartTag, "dl") => self.lists.push(offset),
+            (TagKind::EndTag, "dl") => {
+                self.report_term();
+
+                if self.lists.pop().is_none() {
+                    self.report(
+                        offset,
+                        Rule::UnexpectedListEnd,
+                        String::from("</DL> without <DL>"),
+                    );
+                }
+            }
+            (TagKind::StartTag, "dt") => self.term = Some(offset),
+            (TagKind::StartTag, "a") => {
+                let is_empty = |name| attribute(&tag, name).is_none_or(|url| url.trim().is_empty());
+                let is_feed =
+                    attribute(&tag, "feed").is_some_and(|value| value.eq_ignore_ascii_case("true"));
+
+                if is_feed && is_empty("feedurl") {
+                    self.report(offset, Rule::EmptyHref, String::from("empty FEEDURL"));
+                } else if !is_feed && is_empty("href") {
+                    self.report(offset, Rule::EmptyHref, String::from("empty HREF"));
+                }
+
+                self.check_dates(&tag, offset);
+                self.check_icons(&tag, offset);
+            }
+            (TagKind::StartTag, "h3") => {
+                self.check_dates(&tag, offset);
+
+                let is_toolbar = attribute(&tag, "personal_toolbar_folder")
+                    .is_some_and(|value| value.eq_ignore_ascii_case("true"));
+
+                match (is_toolbar, self.toolbar) {
+                    (true, Some(first)) => {
+                        let (line, column) = self.locator.position(first);
+
+                        self.report(
+                            offset,
+                            Rule::DuplicateToolbarFolder,
+                            format!(
+                                "another PERSONAL_TOOLBAR_FOLDER, the first one is at {}:{}",
+                                line, column
+                            ),
+                        );
+                    }
+                    (true, None) => self.toolbar = Some(offset),
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+
+        TokenSinkResult::Continue
+    }
+
+    fn check_dates(&mut self, tag: &Tag, offset: usize) {
+        let mut dates = vec![];
+
+        for name in ["add_date", "last_visit", "last_modified"] {
+            let date = attribute(tag, name)
+                .map(Timestamp::from)
+                .unwrap_or_default();
+
+            if !date.is_empty() && date.as_micros().is_none() {
+                self.report(
+                    offset,
+                    Rule::InvalidDate,
+                    format!("invalid {} {:?}", name.to_uppercase(), date.as_str()),
+                );
+            }
+
+            dates.push(date.as_micros().filter(|micros| *micros > 0));
+        }
+
+        if let (Some(added), Some(modified)) = (dates[0], dates[2]) {
+            if modified < added {
+                self.report(
+                    offset,
+                    Rule::ModifiedBeforeAdded,
+                    String::from("LAST_MODIFIED is earlier than ADD_DATE"),
+                );
+            }
+        }
+    }
+
+    fn check_icons(&mut self, tag: &Tag, offset: usize) {
+        for name in ["icon", "icon_uri"] {
+            let uri = attribute(tag, name).unwrap_or_default();
+
+            if icon::is_data_uri(uri) {
+                if let Err(err) = Icon::from_data_uri(uri).and_then(Icon::corrected) {
+                    self.report(
+                        offset,
+                        Rule::InvalidIcon,
+                        format!("{} of {}", err, name.to_uppercase()),
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl TokenSink for LintSink<'_> {
+    type Handle = ();
+
+    fn process_token(&mut self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        match token {
+            Token::TagToken(tag) => return self.process_tag(tag),
+            Token::CommentToken(_) => self.locator.skip_comment(),
+            Token::EOFToken => {
+                self.report_term();
+
+                for offset in std::mem::take(&mut self.lists) {
+                    self.report(offset, Rule::UnclosedList, String::from("unclosed <DL>"));
+                }
+            }
+            _ => (),
+        }
+
+        TokenSinkResult::Continue
+    }
+}
+
+/// Gets the value of an attribute of a tag.
+fn attribute<'t>(tag: &'t Tag, name: &str) -> Option<&'t str> {
+    tag.attrs
+        .iter()
+        .find(|attr| &*attr.name.local == name)
+        .map(|attr| &*attr.value)
+}
+
+/// Checks a raw document, the diagnostics are sorted by their position.
+pub(crate) fn lint(raw: &str) -> Vec<Diagnostic> {
+    let sink = LintSink {
+        locator: Locator::new(raw),
+        diagnostics: vec![],
+        lists: vec![],
+        term: None,
+        toolbar: None,
+    };
+    let mut tokenizer = Tokenizer::new(sink, TokenizerOpts::default());
+    let mut queue = BufferQueue::new();
+
+    queue.push_back(StrTendril::from(raw));
+    let _ = tokenizer.feed(&mut queue);
+    tokenizer.end();
+
+    let mut diagnostics = tokenizer.sink.diagnostics;
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    diagnostics
+}
+
+#[test]
+fn should_accept_the_exports() {
+    for path in [
+        "./res/chromium.html",
+        "./res/firefox.html",
+        "./res/netscape.html",
+    ] {
+        let raw = std::fs::read_to_string(path).unwrap();
+
+        assert_eq!(lint(&raw), vec![], "{}", path);
+    }
+}
+
+#[test]
+fn should_locate_diagnostics() {
+    let raw = "<!-- <DL> -->
+<DL><p>
+    <DT><H3 PERSONAL_TOOLBAR_FOLDER=\"true\">Bar</H3>
+    <DL><p>
+        <DT><A HREF=\"x\" ADD_DATE=\"20\" LAST_MODIFIED=\"10\">x</A>
+        <DT>é <DT><A HREF=\"y\" ICON=\"data:image/png;base64,aGVsbG8=\">y</A>
+    </DL><p>
+    <DT><h3 personal_toolbar_folder=\"TRUE\">Bar</h3>
+    <DL><p>
+</DL>";
+    let diagnostics = lint(raw)
+        .into_iter()
+        .map(|d| (d.rule, d.line, d.column))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            (Rule::UnclosedList, 2, 1),
+            (Rule::ModifiedBeforeAdded, 5, 13),
+            (Rule::MalformedItem, 6, 9),
+            (Rule::InvalidIcon, 6, 19),
+            (Rule::DuplicateToolbarFolder, 8, 9),
+        ]
+    );
+}
+
+#[test]
+fn should_accept_rendered_feed() {
+    use crate::feed::FeedBuilder;
+    use crate::item::Item;
+    use crate::Netscape;
+
+    let feed = |url: &str| {
+        Item::Feed(
+            FeedBuilder::default()
+                .feed_url(url)
+                .title("feed")
+                .build()
+                .unwrap(),
+        )
+    };
+    let netscape = Netscape {
+        title: String::from("t"),
+        h1: String::from("h"),
+        children: vec![feed("https://example.com/feed.xml"), feed("")],
+    };
+    let diagnostics = lint(&netscape.to_html().unwrap());
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, Rule::EmptyHref);
+    assert_eq!(diagnostics[0].message, "empty FEEDURL");
+    assert_eq!(diagnostics[0].line, 10);
+}
+
+#[test]
+fn should_locate_diagnostics_after_bookmarklets() {
+    let raw = r#"<DL><p>
+    <DT><A HREF="javascript:document.write('</a><dt><a href=x>')" ADD_DATE='1'>b</A> <DT><A HREF="">y</A>
+</DL>"#;
+    let diagnostics = lint(raw);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, Rule::EmptyHref);
+    assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 90));
+}
+
+#[test]
+fn should_report_unexpected_list_end() {
+    let diagnostics = lint("<DL></DL>\n</dl>");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, Rule::UnexpectedListEnd);
+    assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 1));
+}
@@ -0,0 +1,403 @@
+//! Contains the merge of two [Netscape] documents into a new one.
+//!
+//! The folders are matched by their title path : two folders are the same when they have the same
+//! title in the same parent folder. The bookmarks toolbar and the unfiled bookmarks folders are
+//! matched whatever their titles, since each browser names them differently. The bookmarks, the
+//! feeds and the web slices of matched folders are unioned by their normalized url.
+//!
+//! A new item is inserted after the item that precedes it in the merged document, once matched, so
+//! the order of the siblings is kept. A separator is matched when it follows the same item.
+//!
+//! [Netscape]: crate::Netscape
+use crate::dedupe::merge_metadata;
+use crate::item::Item;
+use crate::url::Rules;
+use crate::Bookmark;
+use crate::Folder;
+
+/// Defines which title is kept when two matched bookmarks have different titles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TitleConflict {
+    /// Keeps the title of the document that is merged into, it is the default rule.
+    #[default]
+    KeepOurs,
+
+    /// Keeps the title of the merged document.
+    KeepTheirs,
+
+    /// Keeps the longest title in characters, the one of the document merged into is kept on equal lengths.
+    KeepLongest,
+}
+
+/// Defines which dates are kept when two matched bookmarks have different dates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateConflict {
+    /// Keeps the earliest `add_date` and the latest `last_visit` and `last_modified`, it is the default rule.
+    #[default]
+    Combine,
+
+    /// Keeps the dates of the document that is merged into.
+    KeepOurs,
+
+    /// Keeps the dates of the merged document, unless they are empty.
+    KeepTheirs,
+}
+
+/// Defines how two documents are merged by [Netscape::merge].
+///
+/// [Netscape::merge]: crate::Netscape::merge
+#[derive(Builder, Clone, Debug, Default, PartialEq, Eq)]
+#[builder(setter(into))]
+pub struct MergeOptions {
+    /// The `rules` attribute stores how the urls are normalized to match the bookmarks.
+    #[builder(default)]
+    pub rules: Rules,
+
+    /// The `title` attribute stores the rule applied to the titles of the matched bookmarks.
+    #[builder(default)]
+    pub title: TitleConflict,
+
+    /// The `dates` attribute stores the rule applied to the dates of the matched bookmarks.
+    #[builder(default)]
+    pub dates: DateConflict,
+}
+
+/// Describes an item added or updated by a merge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergedItem {
+    /// The `folder` attribute stores the titles of the folders containing the item, from the root.
+    /// It is empty for a top-level item.
+    pub folder: Vec<String>,
+
+    /// The `item` attribute is the added item, or the updated one as it is in the merged document.
+    pub item: Item,
+}
+
+/// Summarizes what a merge changed in the document merged into, in the order of the merged document.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MergeSummary {
+    /// The `added` [Vec] stores the items that only exist in the merged document.
+    /// A new folder is reported once, with all its children.
+    pub added: Vec<MergedItem>,
+
+    /// The `updated` [Vec] stores the bookmarks whose metadata were completed by the merged document.
+    pub updated: Vec<MergedItem>,
+}
+
+impl MergeSummary {
+    /// Checks if the merge didn't change anything.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty()
+    }
+}
+
+fn same_folder(ours: &Folder, theirs: &Folder) -> bool {
+    if ours.personal_toolbar_folder || theirs.personal_toolbar_folder {
+        ours.personal_toolbar_folder && theirs.personal_toolbar_folder
+    } else if ours.unfiled_bookmarks_folder || theirs.unfiled_bookmarks_folder {
+        ours.unfiled_bookmarks_folder && theirs.unfiled_bookmarks_folder
+    } else {
+        ours.title == theirs.title
+    }
+}
+
+fn same_item(ours: &Item, theirs: &Item, rules: &Rules) -> bool {
+    match (ours, theirs) {
+        (Item::Subfolder(o), Item::Subfolder(t)) => same_folder(o, t),
+        (Item::Shortcut(o), Item::Shortcut(t)) => o.same_url(t, rules),
+        (Item::Feed(o), Item::Feed(t)) => rules.same(&o.feed_url, &t.feed_url),
+        (Item::WebSlice(o), Item::WebSlice(t)) => rules.same(&o.href, &t.href),
+        _ => false,
+    }
+}
+
+/// Merges the metadata of a bookmark into the matched one, it tells if the bookmark changed.
+fn merge_bookmark(ours: &mut Bookmark, theirs: &Bookmark, options: &MergeOptions) -> bool {
+    let before = ours.clone();
+
+    merge_metadata(ours, theirs);
+
+    ours.title = match options.title {
+        TitleConflict::KeepTheirs if !theirs.title.is_empty() => theirs.title.clone(),
+        TitleConflict::KeepLongest
+            if theirs.title.chars().count() > before.title.chars().count() =>
+        {
+            theirs.title.clone()
+        }
+        _ => ours.title.clone(),
+    };
+
+    match options.dates {
+        DateConflict::Combine => (),
+        DateConflict::KeepOurs => {
+            ours.add_date = before.add_date.clone();
+            ours.last_visit = before.last_visit.clone();
+            ours.last_modified = before.last_modified.clone();
+        }
+        DateConflict::KeepTheirs => {
+            for (date, theirs, before) in [
+                (&mut ours.add_date, &theirs.add_date, &before.add_date),
+                (&mut ours.last_visit, &theirs.last_visit, &before.last_visit),
+                (
+                    &mut ours.last_modified,
+                    &theirs.last_modified,
+                    &before.last_modified,
+                ),
+            ] {
+                *date = if theirs.is_empty() {
+                    before.clone()
+                } else {
+                    theirs.clone()
+                };
+            }
+        }
+    }
+
+    *ours != before || ours.icon != before.icon || ours.icon_uri != before.icon_uri
+}
+
+/// Merges the items of the merged document into the matched list of the document merged into.
+pub(crate) fn merge_children(
+    ours: &mut Vec<Item>,
+    theirs: &[Item],
+    options: &MergeOptions,
+    folder: &mut Vec<String>,
+    summary: &mut MergeSummary,
+) {
+    // the position in our list of the last matched or added item
+    let mut anchor: Option<usize> = None;
+
+    for (position, item) in theirs.iter().enumerate() {
+        let next = anchor.map_or(0, |index| index + 1);
+        let matched = match item {
+            Item::Separator => {
+                Some(next).filter(|index| ours.get(*index).is_some_and(Item::is_separator))
+            }
+            _ => ours
+                .iter()
+                .position(|candidate| same_item(candidate, item, &options.rules)),
+        };
+
+        match (matched, item) {
+            (Some(index), Item::Subfolder(theirs)) => {
+                if let Some(matched) = ours[index].as_subfolder_mut() {
+                    if matched.description.is_empty() {
+                        matched.description = theirs.description.clone();
+                    }
+
+                    folder.push(matched.title.clone());
+                    merge_children(
+                        &mut matched.children,
+                        &theirs.children,
+                        options,
+                        folder,
+                        summary,
+                    );
+                    folder.pop();
+                }
+            }
+            (Some(index), Item::Shortcut(theirs)) => {
+                if let Some(matched) = ours[index].as_shortcut_mut() {
+                    if merge_bookmark(matched, theirs, options) {
+                        summary.updated.push(MergedItem {
+                            folder: folder.clone(),
+                            item: ours[index].clone(),
+                        });
+                    }
+                }
+            }
+            (Some(_), _) => (),
+            (None, _) => {
+                // without a matched predecessor, the item goes before the next matched sibling
+                let index = match anchor {
+                    Some(_) => next,
+                    None => theirs[position + 1..]
+                        .iter()
+                        .filter(|other| !other.is_separator())
+                        .find_map(|other| {
+                            ours.iter()
+                                .position(|candidate| same_item(candidate, other, &options.rules))
+                        })
+                        .unwrap_or(ours.len()),
+                };
+
+                ours.insert(index, item.clone());
+                summary.added.push(MergedItem {
+                    folder: folder.clone(),
+                    item: item.clone(),
+                });
+                anchor = Some(index);
+                continue;
+            }
+        }
+
+        anchor = matched;
+    }
+}
+
+#[allow(dead_code)]
+fn bookmark(href: &str, title: &str, add_date: &str) -> Item {
+    use crate::bookmark::BookmarkBuilder;
+
+    Item::Shortcut(
+        BookmarkBuilder::default()
+            .href(href)
+            .title(title)
+            .add_date(add_date)
+            .build()
+            .unwrap(),
+    )
+}
+
+#[test]
+fn should_union_bookmarks_of_matched_folders() {
+    use crate::folder::FolderBuilder;
+
+    let mut ours = vec![
+        Item::Subfolder(
+            FolderBuilder::default()
+                .title("Bookmarks bar")
+                .personal_toolbar_folder(true)
+                .children(vec![bookmark("https://a.com/", "a", "10")])
+                .build()
+                .unwrap(),
+        ),
+        bookmark("https://b.com/", "b", "20"),
+    ];
+
+    let theirs = vec![
+        bookmark("http://www.b.com", "b", "20"),
+        Item::Subfolder(
+            FolderBuilder::default()
+                .title("Bookmarks Toolbar")
+                .personal_toolbar_folder(true)
+                .children(vec![
+                    bookmark("https://c.com/", "c", "30"),
+                    bookmark("https://a.com/", "a", "10"),
+                ])
+                .build()
+                .unwrap(),
+        ),
+        Item::Separator,
+        Item::Subfolder(FolderBuilder::default().title("New").build().unwrap()),
+    ];
+
+    let mut summary = MergeSummary::default();
+    merge_children(
+        &mut ours,
+        &theirs,
+        &MergeOptions::default(),
+        &mut vec![],
+        &mut summary,
+    );
+
+    let toolbar = ours[0].take_subfolder().unwrap();
+    assert_eq!(toolbar.title, "Bookmarks bar");
+    assert_eq!(
+        toolbar.children,
+        vec![
+            bookmark("https://c.com/", "c", "30"),
+            bookmark("https://a.com/", "a", "10")
+        ]
+    );
+    assert_eq!(ours.len(), 4);
+    assert_eq!(ours[1], Item::Separator);
+    assert!(summary.updated.is_empty());
+    assert_eq!(summary.added.len(), 3);
+    assert_eq!(summary.added[0].folder, vec!["Bookmarks bar"]);
+    assert_eq!(summary.added[1].item, Item::Separator);
+    assert!(summary.added[2].folder.is_empty());
+}
+
+#[test]
+fn should_keep_the_order_of_merged_items() {
+    let merge = |mut ours: Vec<Item>, theirs: &[Item]| {
+        let mut summary = MergeSummary::default();
+
+        merge_children(
+            &mut ours,
+            theirs,
+            &MergeOptions::default(),
+            &mut vec![],
+            &mut summary,
+        );
+        (ours, summary)
+    };
+
+    let a = bookmark("https://a.com/", "a", "");
+    let b = bookmark("https://b.com/", "b", "");
+    let c = bookmark("https://c.com/", "c", "");
+    let d = bookmark("https://d.com/", "d", "");
+
+    let (ours, summary) = merge(vec![a.clone()], &[b.clone(), Item::Separator, c.clone()]);
+    assert_eq!(ours, vec![a.clone(), b.clone(), Item::Separator, c.clone()]);
+    assert_eq!(summary.added.len(), 3);
+
+    let (ours, _) = merge(
+        vec![a.clone(), c.clone()],
+        &[a.clone(), b.clone(), c.clone(), d.clone()],
+    );
+    assert_eq!(ours, vec![a.clone(), b.clone(), c.clone(), d]);
+
+    let (ours, summary) = merge(ours.clone(), &[a.clone(), Item::Separator, b.clone()]);
+    assert_eq!(ours[..3], [a.clone(), Item::Separator, b.clone()]);
+    assert_eq!(summary.added.len(), 1);
+
+    let (_, summary) = merge(ours, &[a, Item::Separator, b, c]);
+    assert!(summary.is_empty());
+}
+
+#[test]
+fn should_resolve_bookmark_conflicts() {
+    let theirs = vec![bookmark("https://a.com/", "a longer title", "10")];
+
+    let merge = |options: MergeOptions| {
+        let mut ours = vec![bookmark("https://a.com/", "a", "20")];
+        let mut summary = MergeSummary::default();
+
+        merge_children(&mut ours, &theirs, &options, &mut vec![], &mut summary);
+        (ours, summary)
+    };
+
+    let (ours, summary) = merge(MergeOptions::default());
+    assert_eq!(ours, vec![bookmark("https://a.com/", "a", "10")]);
+    assert_eq!(summary.updated.len(), 1);
+
+    let (ours, _) = merge(
+        MergeOptionsBuilder::default()
+            .title(TitleConflict::KeepLongest)
+            .dates(DateConflict::KeepOurs)
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(
+        ours,
+        vec![bookmark("https://a.com/", "a longer title", "20")]
+    );
+
+    let (ours, _) = merge(
+        MergeOptionsBuilder::default()
+            .title(TitleConflict::KeepTheirs)
+            .dates(DateConflict::KeepTheirs)
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(
+        ours,
+        vec![bookmark("https://a.com/", "a longer title", "10")]
+    );
+    let mut ours = bookmark("https://a.com/", "abc", "");
+    let longest = MergeOptionsBuilder::default()
+        .title(TitleConflict::KeepLongest)
+        .build()
+        .unwrap();
+
+    merge_bookmark(
+        ours.as_shortcut_mut().unwrap(),
+        bookmark("https://a.com/", "été", "")
+            .take_shortcut()
+            .unwrap(),
+        &longest,
+    );
+    assert_eq!(ours, bookmark("https://a.com/", "abc", ""));
+}
@@ -1,10 +1,18 @@
+use indexmap::IndexMap;
 use kuchiki::{Attribute, NodeRef};
 
+use crate::parser::{ORDER_ATTRIBUTE, ORDER_NAMESPACE};
+
 /// Augments the [NodeRef] struct with conveniant helpers
 pub trait NodeRefExt {
+    #[allow(dead_code)]
     fn select_text(&self, selector: &str) -> Option<String>;
     fn is_element(&self, local_name: &str) -> bool;
     fn select_attribute(&self, tag_name: &str) -> Option<Attribute>;
+    fn select_extra_attributes(&self, known: &[&str]) -> IndexMap<String, String>;
+    fn has_flag(&self, attribute_name: &str) -> bool;
+    fn select_description(&self) -> Option<NodeRef>;
+    fn description_text(&self) -> String;
 }
 
 impl NodeRefExt for NodeRef {
@@ -51,6 +59,81 @@ impl NodeRefExt for NodeRef {
 
         attribute
     }
+
+    fn select_extra_attributes(&self, known: &[&str]) -> IndexMap<String, String> {
+        let mut extra = IndexMap::new();
+
+        if let Some(element) = self.as_element() {
+            let attributes = element.attributes.borrow();
+            let mut names = attributes
+                .map
+                .keys()
+                .filter(|exp_name| exp_name.ns.is_empty())
+                .map(|exp_name| exp_name.local.to_string())
+                .collect::<Vec<_>>();
+
+            // the parser records the order of the attributes, the map is sorted otherwise
+            let order = attributes
+                .map
+                .iter()
+                .find(|(exp_name, _)| {
+                    &*exp_name.ns == ORDER_NAMESPACE && &*exp_name.local == ORDER_ATTRIBUTE
+                })
+                .map(|(_, attr)| attr.value.clone());
+
+            if let Some(order) = order {
+                names.sort_by_key(|name| order.split(' ').position(|n| n == name));
+            }
+
+            for name in names {
+                let upper = name.to_ascii_uppercase();
+
+                if !known.contains(&upper.as_str()) {
+                    if let Some(value) = attributes.get(name.as_str()) {
+                        extra.insert(upper, String::from(value));
+                    }
+                }
+            }
+        }
+
+        extra
+    }
+
+    fn has_flag(&self, attribute_name: &str) -> bool {
+        self.select_attribute(attribute_name)
+            .is_some_and(|attribute| attribute.value.eq_ignore_ascii_case("true"))
+    }
+
+    fn select_description(&self) -> Option<NodeRef> {
+        let mut description = None;
+
+        if let Some(dt) = self.parent().filter(|n| n.is_element("DT")) {
+            let next = dt.following_siblings().find(|n| n.as_element().is_some());
+
+            if let Some(dd) = next {
+                if dd.is_element("DD") {
+                    description = Some(dd);
+                }
+            }
+        }
+
+        description
+    }
+
+    fn description_text(&self) -> String {
+        let mut text = String::new();
+
+        // the HTML parser nests the folder's list and the separators in the <DD/> element
+        for child in self.children() {
+            if child.is_element("DL") || child.is_element("HR") {
+                break;
+            }
+
+            text.push_str(&child.text_contents());
+        }
+
+        text.trim().to_string()
+    }
 }
 
 #[test]
@@ -60,8 +143,8 @@ fn should_check_dl_element() {
 
     let dl = parse_html().one(r"<DL></DL>").select_first("DL").unwrap();
 
-    assert_eq!(dl.as_node().is_element("DL"), true);
-    assert_eq!(dl.as_node().is_element("DT"), false);
+    assert!(dl.as_node().is_element("DL"));
+    assert!(!dl.as_node().is_element("DT"));
 }
 
 #[test]
@@ -78,6 +161,56 @@ fn should_select_href_attribute() {
     assert_eq!(attribute.value, "Test")
 }
 
+#[test]
+fn should_select_extra_attributes_in_order() {
+    use kuchiki::traits::TendrilSink;
+
+    let html =
+        r#"<A HREF="url" PRIVATE="1" ADD_DATE="date" LAST_CHARSET="UTF-8" ID="rdf:#$1">a</A>"#;
+    let document = crate::parser::parse_html().one(html);
+    let a = document.select_first("A").unwrap();
+    let extra = a.as_node().select_extra_attributes(&["HREF", "ADD_DATE"]);
+
+    assert_eq!(
+        extra.into_iter().collect::<Vec<_>>(),
+        vec![
+            (String::from("PRIVATE"), String::from("1")),
+            (String::from("LAST_CHARSET"), String::from("UTF-8")),
+            (String::from("ID"), String::from("rdf:#$1")),
+        ]
+    );
+}
+
+#[test]
+fn should_select_description() {
+    use kuchiki::parse_html;
+    use kuchiki::traits::TendrilSink;
+
+    let html = r"<DL><p><DT><A>a</A><DD>  first
+second
+<HR><DT><A>b</A></DL>";
+    let document = parse_html().one(html);
+    let anchors = document.select("A").unwrap().collect::<Vec<_>>();
+
+    let dd = anchors[0].as_node().select_description().unwrap();
+    assert_eq!(dd.description_text(), "first\nsecond");
+    assert!(anchors[1].as_node().select_description().is_none());
+}
+
+#[test]
+fn should_not_select_the_description_of_an_ancestor() {
+    use kuchiki::parse_html;
+    use kuchiki::traits::TendrilSink;
+
+    let html = r"<DL><p><DT><H3>folder</H3><DL><p><A>a</A></DL><p><DD>folder</DL>";
+    let document = parse_html().one(html);
+    let anchor = document.select_first("A").unwrap();
+    let h3 = document.select_first("H3").unwrap();
+
+    assert!(anchor.as_node().select_description().is_none());
+    assert!(h3.as_node().select_description().is_some());
+}
+
 #[test]
 fn should_ignore_selector_case() {
     use kuchiki::parse_html;
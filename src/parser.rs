@@ -0,0 +1,148 @@
+//! Contains the HTML parser used by the [Netscape] constructors.
+//!
+//! The DOM of `kuchiki` stores the attributes of an element in a sorted map, the order in which
+//! they were written is lost. This parser wraps the `kuchiki` [Sink] and records that order in a
+//! namespaced attribute, it is read back by [NodeRefExt::select_extra_attributes].
+//!
+//! [Netscape]: crate::Netscape
+//! [NodeRefExt::select_extra_attributes]: crate::node_ref_ext::NodeRefExt::select_extra_attributes
+use html5ever::tendril::StrTendril;
+use html5ever::tree_builder::{ElementFlags, NodeOrText, QuirksMode, TreeSink};
+use html5ever::{Attribute, ExpandedName, LocalName, Namespace, ParseOpts, Parser, QualName};
+use kuchiki::{NodeRef, Sink};
+use std::borrow::Cow;
+
+/// The namespace of the attribute holding the original order of the attributes.
+pub(crate) const ORDER_NAMESPACE: &str = "urn:bookmarkt";
+
+/// The local name of the attribute holding the original order of the attributes.
+pub(crate) const ORDER_ATTRIBUTE: &str = "attributes-order";
+
+/// Creates a parser that builds a `kuchiki` DOM and keeps the order of the attributes.
+pub(crate) fn parse_html() -> Parser<OrderedSink> {
+    let sink = OrderedSink {
+        sink: kuchiki::parse_html().tokenizer.sink.sink,
+    };
+
+    html5ever::parse_document(sink, ParseOpts::default())
+}
+
+/// Records the order of the attributes in a namespaced attribute, when there is more than one.
+pub(crate) fn record_order(attrs: &mut Vec<Attribute>) {
+    if attrs.len() > 1 {
+        let order = attrs
+            .iter()
+            .map(|attr| attr.name.local.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        attrs.push(Attribute {
+            name: QualName::new(
+                None,
+                Namespace::from(ORDER_NAMESPACE),
+                LocalName::from(ORDER_ATTRIBUTE),
+            ),
+            value: StrTendril::from(order),
+        });
+    }
+}
+
+/// Delegates the construction of the DOM to the `kuchiki` [Sink].
+pub(crate) struct OrderedSink {
+    sink: Sink,
+}
+
+impl TreeSink for OrderedSink {
+    type Handle = NodeRef;
+    type Output = NodeRef;
+
+    fn finish(self) -> NodeRef {
+        self.sink.finish()
+    }
+
+    fn parse_error(&mut self, msg: Cow<'static, str>) {
+        self.sink.parse_error(msg)
+    }
+
+    fn get_document(&mut self) -> NodeRef {
+        self.sink.get_document()
+    }
+
+    fn elem_name<'a>(&'a self, target: &'a NodeRef) -> ExpandedName<'a> {
+        self.sink.elem_name(target)
+    }
+
+    fn create_element(
+        &mut self,
+        name: QualName,
+        mut attrs: Vec<Attribute>,
+        flags: ElementFlags,
+    ) -> NodeRef {
+        record_order(&mut attrs);
+        self.sink.create_element(name, attrs, flags)
+    }
+
+    fn create_comment(&mut self, text: StrTendril) -> NodeRef {
+        self.sink.create_comment(text)
+    }
+
+    fn create_pi(&mut self, target: StrTendril, data: StrTendril) -> NodeRef {
+        self.sink.create_pi(target, data)
+    }
+
+    fn append(&mut self, parent: &NodeRef, child: NodeOrText<NodeRef>) {
+        self.sink.append(parent, child)
+    }
+
+    fn append_based_on_parent_node(
+        &mut self,
+        element: &NodeRef,
+        prev_element: &NodeRef,
+        child: NodeOrText<NodeRef>,
+    ) {
+        self.sink
+            .append_based_on_parent_node(element, prev_element, child)
+    }
+
+    fn append_doctype_to_document(
+        &mut self,
+        name: StrTendril,
+        public_id: StrTendril,
+        system_id: StrTendril,
+    ) {
+        self.sink
+            .append_doctype_to_document(name, public_id, system_id)
+    }
+
+    fn mark_script_already_started(&mut self, node: &NodeRef) {
+        self.sink.mark_script_already_started(node)
+    }
+
+    fn get_template_contents(&mut self, target: &NodeRef) -> NodeRef {
+        self.sink.get_template_contents(target)
+    }
+
+    fn same_node(&self, x: &NodeRef, y: &NodeRef) -> bool {
+        self.sink.same_node(x, y)
+    }
+
+    fn set_quirks_mode(&mut self, mode: QuirksMode) {
+        self.sink.set_quirks_mode(mode)
+    }
+
+    fn append_before_sibling(&mut self, sibling: &NodeRef, new_node: NodeOrText<NodeRef>) {
+        self.sink.append_before_sibling(sibling, new_node)
+    }
+
+    fn add_attrs_if_missing(&mut self, target: &NodeRef, attrs: Vec<Attribute>) {
+        self.sink.add_attrs_if_missing(target, attrs)
+    }
+
+    fn remove_from_parent(&mut self, target: &NodeRef) {
+        self.sink.remove_from_parent(target)
+    }
+
+    fn reparent_children(&mut self, node: &NodeRef, new_parent: &NodeRef) {
+        self.sink.reparent_children(node, new_parent)
+    }
+}
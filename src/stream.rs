@@ -0,0 +1,588 @@
+//! Contains the streaming parser, it reads a document as a flow of [Event]s without building its DOM.
+//!
+//! The exports of the browsers can weigh hundreds of megabytes, mostly because of the `ICON` data.
+//! The [Events] iterator tokenizes the document by chunks and only keeps the item being parsed in memory,
+//! each item is parsed by the same models as [Netscape::from_file]. The streaming parser is lenient,
+//! see [ParseMode::Lenient].
+//!
+//! [Netscape::from_file]: crate::Netscape::from_file
+//! [ParseMode::Lenient]: crate::ParseMode::Lenient
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::states::RawKind;
+use html5ever::tokenizer::{
+    BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use html5ever::{namespace_url, ns, Attribute, LocalName, QualName};
+use kuchiki::{ExpandedName, NodeRef};
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::str;
+
+use crate::item::Item;
+use crate::parser::record_order;
+use crate::Error;
+use crate::Folder;
+
+/// The size of the chunks read from the document.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Represents a step of the streaming parser, in the document order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// The content of the `<TITLE/>` tag.
+    Title(String),
+
+    /// The content of the `<H1/>` tag.
+    Heading(String),
+
+    /// A folder starts, its `children` are empty : they follow as events until the matching [Event::LeaveFolder].
+    EnterFolder(Folder),
+
+    /// A bookmark, a feed, a web slice or a separator of the current folder, it is never a subfolder.
+    Item(Item),
+
+    /// The current folder ends.
+    LeaveFolder,
+}
+
+/// The kind of a `<DL/>` list being parsed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum List {
+    /// A top-level list of the document.
+    Root,
+
+    /// The list of a folder.
+    Folder,
+
+    /// A list that belongs to no folder, its items are skipped like the DOM parser does.
+    Ignored,
+}
+
+/// The text being captured.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Capture {
+    Nothing,
+    Title,
+    Heading,
+    /// The content of the `<A/>` or `<H3/>` tag of the pending item.
+    Term,
+    /// The content of the `<DD/>` tag following the pending item.
+    Description,
+}
+
+/// An `<A/>` or `<H3/>` tag waiting for its description or its list.
+struct Pending {
+    tag: Tag,
+    text: String,
+    description: Option<String>,
+}
+
+impl Pending {
+    fn is_folder(&self) -> bool {
+        &*self.tag.name == "h3"
+    }
+
+    /// Parses the item with the DOM models, from a small tree holding only this item.
+    fn into_item(self) -> Option<Item> {
+        let list = element(LocalName::from("dl"), vec![]);
+        let term = element(LocalName::from("dt"), vec![]);
+        let node = element(self.tag.name, self.tag.attrs);
+
+        node.append(NodeRef::new_text(self.text));
+        term.append(node);
+        list.append(term.clone());
+
+        if let Some(description) = self.description {
+            let dd = element(LocalName::from("dd"), vec![]);
+
+            dd.append(NodeRef::new_text(description));
+            list.append(dd);
+        }
+
+        Item::from_node(&term)
+    }
+}
+
+fn element(name: LocalName, mut attrs: Vec<Attribute>) -> NodeRef {
+    record_order(&mut attrs);
+
+    NodeRef::new_element(
+        QualName::new(None, ns!(html), name),
+        attrs.into_iter().map(|attr| {
+            (
+                ExpandedName::new(attr.name.ns, attr.name.local),
+                kuchiki::Attribute {
+                    prefix: attr.name.prefix,
+                    value: attr.value.to_string(),
+                },
+            )
+        }),
+    )
+}
+
+/// Turns the tokens of the document into [Event]s.
+struct EventSink {
+    events: VecDeque<Event>,
+    lists: Vec<List>,
+    pending: Option<Pending>,
+    capture: Capture,
+    text: String,
+    /// Tells if a `<DT/>` tag is open, the items are only parsed in it.
+    in_term: bool,
+}
+
+impl EventSink {
+    fn new() -> Self {
+        EventSink {
+            events: VecDeque::new(),
+            lists: vec![],
+            pending: None,
+            capture: Capture::Nothing,
+            text: String::new(),
+            in_term: false,
+        }
+    }
+
+    /// Checks if the items of the current list belong to the document.
+    fn is_listed(&self) -> bool {
+        matches!(self.lists.last(), Some(List::Root | List::Folder))
+    }
+
+    /// Emits the pending item, a folder without list is empty.
+    fn flush(&mut self) {
+        self.capture = Capture::Nothing;
+
+        match self.pending.take().and_then(Pending::into_item) {
+            Some(Item::Subfolder(folder)) => {
+                self.events.push_back(Event::EnterFolder(folder));
+                self.events.push_back(Event::LeaveFolder);
+            }
+            Some(item) => self.events.push_back(Event::Item(item)),
+            None => (),
+        }
+    }
+
+    fn open_list(&mut self) {
+        self.capture = Capture::Nothing;
+        self.in_term = false;
+
+        let list = match self.pending.take() {
+            Some(pending) if pending.is_folder() => match pending.into_item() {
+                Some(Item::Subfolder(folder)) => {
+                    self.events.push_back(Event::EnterFolder(folder));
+                    List::Folder
+                }
+                _ => List::Ignored,
+            },
+            pending => {
+                self.pending = pending;
+                self.flush();
+
+                if self.lists.is_empty() {
+                    List::Root
+                } else {
+                    List::Ignored
+                }
+            }
+        };
+
+        self.lists.push(list);
+    }
+
+    fn close_list(&mut self) {
+        self.flush();
+        self.in_term = false;
+
+        if let Some(List::Folder) = self.lists.pop() {
+            self.events.push_back(Event::LeaveFolder);
+        }
+    }
+
+    fn process_tag(&mut self, tag: Tag) -> TokenSinkResult<()> {
+        match (tag.kind, &*tag.name) {
+            (TagKind::StartTag, "title") => {
+                self.capture = Capture::Title;
+                self.text.clear();
+                return TokenSinkResult::RawData(RawKind::Rcdata);
+            }
+            (TagKind::EndTag, "title") if self.capture == Capture::Title => {
+                self.capture = Capture::Nothing;
+                self.events
+                    .push_back(Event::Title(std::mem::take(&mut self.text)));
+            }
+            (TagKind::StartTag, "h1") => {
+                self.capture = Capture::Heading;
+                self.text.clear();
+            }
+            (TagKind::EndTag, "h1") if self.capture == Capture::Heading => {
+                self.capture = Capture::Nothing;
+                self.events
+                    .push_back(Event::Heading(std::mem::take(&mut self.text)));
+            }
+            (TagKind::StartTag, "dt") => {
+                self.flush();
+                self.in_term = true;
+            }
+            (TagKind::StartTag, "a" | "h3")
+                if self.in_term && self.pending.is_none() && self.is_listed() =>
+            {
+                self.capture = Capture::Term;
+                self.pending = Some(Pending {
+                    tag,
+                    text: String::new(),
+                    description: None,
+                });
+            }
+            (TagKind::EndTag, "a" | "h3") if self.capture == Capture::Term => {
+                self.capture = Capture::Nothing;
+            }
+            (TagKind::StartTag, "dd") => {
+                self.in_term = false;
+                self.capture = Capture::Nothing;
+
+                if let Some(pending) = &mut self.pending {
+                    if pending.description.is_none() {
+                        pending.description = Some(String::new());
+                        self.capture = Capture::Description;
+                    }
+                }
+            }
+            (TagKind::StartTag, "hr") => {
+                self.flush();
+
+                if self.is_listed() {
+                    self.events.push_back(Event::Item(Item::Separator));
+                }
+            }
+            (TagKind::StartTag, "dl") => self.open_list(),
+            (TagKind::EndTag, "dl") => self.close_list(),
+            _ => (),
+        }
+
+        TokenSinkResult::Continue
+    }
+
+    fn process_text(&mut self, text: &str) {
+        match (self.capture, &mut self.pending) {
+            (Capture::Title | Capture::Heading, _) => self.text.push_str(text),
+            (Capture::Term, Some(pending)) => pending.text.push_str(text),
+            (Capture::Description, Some(pending)) => {
+                if let Some(description) = &mut pending.description {
+                    description.push_str(text);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Closes the pending item and the lists left open at the end of the document.
+    fn finish(&mut self) {
+        self.flush();
+
+        while !self.lists.is_empty() {
+            self.close_list();
+        }
+    }
+}
+
+impl TokenSink for EventSink {
+    type Handle = ();
+
+    fn process_token(&mut self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        match token {
+            Token::TagToken(tag) => return self.process_tag(tag),
+            Token::CharacterTokens(text) => self.process_text(&text),
+            Token::EOFToken => self.finish(),
+            _ => (),
+        }
+
+        TokenSinkResult::Continue
+    }
+}
+
+/// Decodes the complete UTF-8 sequences of the bytes, the incomplete trailing sequence is kept for the next chunk.
+///
+/// The invalid sequences are replaced by `U+FFFD`, like the DOM parser does.
+fn decode(bytes: &mut Vec<u8>) -> String {
+    let mut text = String::new();
+    let mut start = 0;
+
+    while start < bytes.len() {
+        match str::from_utf8(&bytes[start..]) {
+            Ok(valid) => {
+                text.push_str(valid);
+                start = bytes.len();
+            }
+            Err(err) => {
+                let end = start + err.valid_up_to();
+                text.push_str(str::from_utf8(&bytes[start..end]).unwrap_or_default());
+
+                match err.error_len() {
+                    Some(len) => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        start = end + len;
+                    }
+                    None => {
+                        start = end;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    bytes.drain(..start);
+    text
+}
+
+/// Iterates over the [Event]s of a document read by chunks, the document must be encoded in UTF-8.
+///
+/// ```rust
+/// use bookmarkt::{Event, Events};
+/// use std::fs::File;
+///
+/// let file = File::open("./res/chromium.html").unwrap();
+/// let folders = Events::new(file)
+///     .filter_map(Result::ok)
+///     .filter(|event| matches!(event, Event::EnterFolder(_)))
+///     .count();
+///
+/// assert_eq!(folders, 3);
+/// ```
+pub struct Events<R> {
+    reader: R,
+    tokenizer: Tokenizer<EventSink>,
+    queue: BufferQueue,
+    /// The bytes read but not decoded yet.
+    bytes: Vec<u8>,
+    done: bool,
+}
+
+impl<R: Read> Events<R> {
+    /// Creates the iterator over the events of a document, it is read lazily.
+    pub fn new(reader: R) -> Self {
+        Events {
+            reader,
+            tokenizer: Tokenizer::new(EventSink::new(), TokenizerOpts::default()),
+            queue: BufferQueue::new(),
+            bytes: vec![],
+            done: false,
+        }
+    }
+
+    /// Reads and tokenizes the next chunk of the document.
+    fn read_chunk(&mut self) -> Result<(), Error> {
+        let mut chunk = vec![0; CHUNK_SIZE];
+
+        let read = loop {
+            match self.reader.read(&mut chunk) {
+                Ok(read) => break read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(Error::Io(err)),
+            }
+        };
+
+        if read == 0 {
+            if !self.bytes.is_empty() {
+                self.bytes.clear();
+                self.queue
+                    .push_back(StrTendril::from_char(char::REPLACEMENT_CHARACTER));
+                let _ = self.tokenizer.feed(&mut self.queue);
+            }
+
+            self.tokenizer.end();
+            self.done = true;
+        } else {
+            self.bytes.extend_from_slice(&chunk[..read]);
+
+            let text = decode(&mut self.bytes);
+
+            if !text.is_empty() {
+                self.queue.push_back(StrTendril::from(text));
+                let _ = self.tokenizer.feed(&mut self.queue);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for Events<R> {
+    type Item = Result<Event, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.tokenizer.sink.events.pop_front() {
+                return Some(Ok(event));
+            }
+
+            if self.done {
+                return None;
+            }
+
+            if let Err(err) = self.read_chunk() {
+                self.done = true;
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+/// Builds the title, the heading and the items of a document from its events.
+pub(crate) fn build<I>(events: I) -> Result<(String, String, Vec<Item>), Error>
+where
+    I: IntoIterator<Item = Result<Event, Error>>,
+{
+    let mut title = String::new();
+    let mut h1 = String::new();
+    let mut children = vec![];
+    let mut folders: Vec<Folder> = vec![];
+
+    for event in events {
+        match event? {
+            Event::Title(text) => title = text,
+            Event::Heading(text) => h1 = text,
+            Event::EnterFolder(folder) => folders.push(folder),
+            Event::Item(item) => match folders.last_mut() {
+                Some(folder) => folder.children.push(item),
+                None => children.push(item),
+            },
+            Event::LeaveFolder => {
+                if let Some(folder) = folders.pop() {
+                    match folders.last_mut() {
+                        Some(parent) => parent.children.push(Item::Subfolder(folder)),
+                        None => children.push(Item::Subfolder(folder)),
+                    }
+                }
+            }
+        }
+    }
+
+    // the folders that are still open are kept, as the DOM parser does
+    while let Some(folder) = folders.pop() {
+        match folders.last_mut() {
+            Some(parent) => parent.children.push(Item::Subfolder(folder)),
+            None => children.push(Item::Subfolder(folder)),
+        }
+    }
+
+    Ok((title, h1, children))
+}
+
+#[test]
+fn should_decode_chunks() {
+    let mut bytes = "é".as_bytes().to_vec();
+    bytes.extend_from_slice(&"è".as_bytes()[..1]);
+
+    assert_eq!(decode(&mut bytes), "é");
+    assert_eq!(bytes.len(), 1);
+
+    bytes.extend_from_slice(&"è".as_bytes()[1..]);
+    bytes.push(0xff);
+
+    assert_eq!(decode(&mut bytes), "è\u{FFFD}");
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn should_stream_events() {
+    let html = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<TITLE>Bookmarks</TITLE>
+<H1>Menu</H1>
+<DL><p>
+    <DT><H3 ADD_DATE="1">Folder</H3>
+    <DD>A folder
+    <DL><p>
+        <DT><A HREF="https://a.com">A &amp; B</A>
+        <DD>A bookmark
+        <HR>
+        <DT><H3>Empty</H3>
+    </DL><p>
+    <DT><A HREF="https://b.com">B</A>
+</DL>"#;
+
+    let events = Events::new(html.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let names = events
+        .iter()
+        .map(|event| match event {
+            Event::Title(title) => format!("title {}", title),
+            Event::Heading(h1) => format!("h1 {}", h1),
+            Event::EnterFolder(folder) => {
+                format!("enter {} {:?}", folder.title, folder.description)
+            }
+            Event::Item(Item::Shortcut(bookmark)) => {
+                format!("bookmark {} {:?}", bookmark.title, bookmark.description)
+            }
+            Event::Item(item) => format!("{:?}", item),
+            Event::LeaveFolder => String::from("leave"),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        names,
+        vec![
+            "title Bookmarks",
+            "h1 Menu",
+            "enter Folder \"A folder\"",
+            "bookmark A & B \"A bookmark\"",
+            "Separator",
+            "enter Empty \"\"",
+            "leave",
+            "leave",
+            "bookmark B \"\"",
+        ]
+    );
+}
+
+#[test]
+fn should_stream_the_same_documents() {
+    use crate::Netscape;
+    use std::fs::File;
+    use std::path::Path;
+
+    for path in [
+        "./res/chromium.html",
+        "./res/firefox.html",
+        "./res/netscape.html",
+    ] {
+        let parsed = Netscape::from_file(Path::new(path)).unwrap();
+        let (title, h1, children) = build(Events::new(File::open(path).unwrap())).unwrap();
+
+        assert_eq!(title, parsed.title);
+        assert_eq!(h1, parsed.h1);
+        assert_eq!(children, parsed.children);
+    }
+}
+
+#[test]
+fn should_stream_byte_by_byte() {
+    use crate::Netscape;
+    use std::path::Path;
+
+    /// Reads one byte at a time, to split the tags and the UTF-8 sequences.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((byte, rest)) if !buf.is_empty() => {
+                    buf[0] = *byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    let raw = std::fs::read("./res/netscape.html").unwrap();
+    let streamed = Netscape::from_events(Events::new(Trickle(&raw))).unwrap();
+
+    assert_eq!(
+        streamed,
+        Netscape::from_file(Path::new("./res/netscape.html")).unwrap()
+    );
+}
@@ -0,0 +1,1048 @@
+//! Contains the three-way synchronization of two [Netscape] documents from their common snapshot.
+//!
+//! The changes from the base snapshot to the remote document, see [Diff], are applied on a copy of the
+//! local document. When both sides changed the same bookmark or folder differently, the local change is
+//! kept and a [Conflict] is reported. A removal never wins against a change : the changed item is kept.
+//! The feeds, the web slices and the separators aren't synchronized, their remote changes are reported
+//! as [Unapplied].
+//!
+//! [Netscape]: crate::Netscape
+//! [Diff]: crate::Diff
+use crate::diff::{self, Change};
+use crate::item::Item;
+use crate::traverse::Iter;
+use crate::tree;
+use crate::url;
+use crate::Bookmark;
+use crate::Folder;
+
+/// Represents a change made differently on both sides, the folders are given by their title paths in the base snapshot.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Conflict {
+    /// The bookmark was renamed differently on each side, the local title is kept.
+    BookmarkRenamed {
+        /// The `href` of the bookmark.
+        href: String,
+        /// The local title.
+        local: String,
+        /// The remote title.
+        remote: String,
+    },
+
+    /// The `href` of the bookmark was changed differently on each side, the local one is kept.
+    HrefChanged {
+        /// The `href` of the bookmark in the base snapshot.
+        href: String,
+        /// The local `href`.
+        local: String,
+        /// The remote `href`.
+        remote: String,
+    },
+
+    /// The bookmark was moved to a different folder on each side, the local folder is kept.
+    BookmarkMoved {
+        /// The `href` of the bookmark.
+        href: String,
+        /// The local folder.
+        local: Vec<String>,
+        /// The remote folder.
+        remote: Vec<String>,
+    },
+
+    /// The folder was renamed differently on each side, the local title is kept.
+    FolderRenamed {
+        /// The path of the folder.
+        path: Vec<String>,
+        /// The local title.
+        local: String,
+        /// The remote title.
+        remote: String,
+    },
+
+    /// The bookmark was removed on one side and changed on the other one, the changed bookmark is kept.
+    BookmarkRemoved {
+        /// The folder containing the bookmark.
+        folder: Vec<String>,
+        /// The kept bookmark.
+        bookmark: Box<Bookmark>,
+    },
+
+    /// The folder was removed on one side and changed on the other one, it is kept.
+    FolderRemoved {
+        /// The path of the folder.
+        path: Vec<String>,
+    },
+}
+
+/// Represents a remote change of a feed, a web slice or a separator, the synchronization doesn't apply them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unapplied {
+    /// The `folder` attribute stores the titles of the folders containing the item, from the root.
+    pub folder: Vec<String>,
+
+    /// The `item` attribute is the added or removed feed, web slice or separator.
+    pub item: Item,
+
+    /// The `removed` attribute tells if the remote document removed the item, else it added it.
+    pub removed: bool,
+}
+
+/// Summarizes a synchronization, see [Netscape::sync].
+///
+/// [Netscape::sync]: crate::Netscape::sync
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SyncReport {
+    /// The `applied` [Vec] stores the remote changes that were applied to the local document.
+    pub applied: Vec<Change>,
+
+    /// The `conflicts` [Vec] stores the changes made differently on both sides.
+    pub conflicts: Vec<Conflict>,
+
+    /// The `unapplied` [Vec] stores the remote changes of the feeds, the web slices and the separators.
+    /// They aren't synchronized, the local ones are kept as they are.
+    pub unapplied: Vec<Unapplied>,
+}
+
+impl SyncReport {
+    /// Checks if both sides changed the same items differently.
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+/// Gets the `href` of the base bookmark changed by a change.
+fn changed_href(change: &Change) -> Option<&str> {
+    match change {
+        Change::BookmarkRemoved { bookmark, .. } | Change::BookmarkMoved { bookmark, .. } => {
+            Some(&bookmark.href)
+        }
+        Change::BookmarkRenamed { href, .. } => Some(href),
+        Change::HrefChanged { from, .. } => Some(from),
+        _ => None,
+    }
+}
+
+/// Rewrites a path of a changed document with the folder names of the base snapshot.
+fn base_path(path: &[String], renames: &[(Vec<String>, String)]) -> Vec<String> {
+    let mut base: Vec<String> = vec![];
+
+    for title in path {
+        let renamed = renames
+            .iter()
+            .find(|(old, to)| old.len() == base.len() + 1 && old.starts_with(&base) && to == title);
+        let segment = match renamed {
+            Some((old, _)) => old[base.len()].clone(),
+            None => title.clone(),
+        };
+
+        base.push(segment);
+    }
+
+    base
+}
+
+/// Rewrites the paths of the changed document in a change with the folder names of the base snapshot.
+fn to_base(change: Change, renames: &[(Vec<String>, String)]) -> Change {
+    match change {
+        Change::BookmarkAdded { folder, bookmark } => Change::BookmarkAdded {
+            folder: base_path(&folder, renames),
+            bookmark,
+        },
+        Change::BookmarkMoved { from, to, bookmark } => Change::BookmarkMoved {
+            from,
+            to: base_path(&to, renames),
+            bookmark,
+        },
+        Change::FolderAdded { path } => Change::FolderAdded {
+            path: base_path(&path, renames),
+        },
+        change => change,
+    }
+}
+
+/// Checks if a change, named as in the base snapshot, happened in a folder of the base snapshot.
+fn touches(change: &Change, path: &[String]) -> bool {
+    match change {
+        Change::BookmarkAdded { folder, .. }
+        | Change::BookmarkRenamed { folder, .. }
+        | Change::HrefChanged { folder, .. } => folder.starts_with(path),
+        Change::BookmarkMoved { to, .. } => to.starts_with(path),
+        Change::FolderAdded { path: added } => added.starts_with(path),
+        Change::FolderRenamed { path: renamed, .. } => renamed.starts_with(path),
+        _ => false,
+    }
+}
+
+/// Finds the path of indexes of the first bookmark with the given normalized url.
+fn find_bookmark(children: &[Item], key: &str) -> Option<Vec<usize>> {
+    for (index, item) in children.iter().enumerate() {
+        match item {
+            Item::Shortcut(bookmark) if url::normalize(&bookmark.href) == key => {
+                return Some(vec![index]);
+            }
+            Item::Subfolder(folder) => {
+                if let Some(mut path) = find_bookmark(&folder.children, key) {
+                    path.insert(0, index);
+                    return Some(path);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// Finds the path of indexes of the first bookmark with the given normalized url directly in a folder.
+fn find_bookmark_in(children: &[Item], titles: &[String], key: &str) -> Option<Vec<usize>> {
+    let mut path = find_folder(children, titles)?;
+    let list = match path.is_empty() {
+        true => children,
+        false => &tree::get(children, &path)?.take_subfolder()?.children,
+    };
+    let index = list
+        .iter()
+        .position(|item| matches!(item, Item::Shortcut(b) if url::normalize(&b.href) == key))?;
+
+    path.push(index);
+    Some(path)
+}
+
+/// Finds the path of indexes of a folder from its title path.
+fn find_folder(children: &[Item], titles: &[String]) -> Option<Vec<usize>> {
+    let mut path = vec![];
+    let mut list = children;
+
+    for title in titles {
+        let index = list
+            .iter()
+            .position(|item| matches!(item, Item::Subfolder(f) if f.title == *title))?;
+
+        path.push(index);
+        list = &list[index].take_subfolder()?.children;
+    }
+
+    Some(path)
+}
+
+/// Gets the children of a folder from its title path, the missing folders are created.
+fn ensure_folder<'a>(children: &'a mut Vec<Item>, titles: &[String]) -> Option<&'a mut Vec<Item>> {
+    let mut list = children;
+
+    for title in titles {
+        let position = list
+            .iter()
+            .position(|item| matches!(item, Item::Subfolder(f) if f.title == *title));
+
+        let index = match position {
+            Some(index) => index,
+            None => {
+                list.push(Item::Subfolder(Folder {
+                    title: title.clone(),
+                    ..Folder::default()
+                }));
+                list.len() - 1
+            }
+        };
+
+        list = &mut list[index].as_subfolder_mut()?.children;
+    }
+
+    Some(list)
+}
+
+/// Applies the remote changes on the local children.
+struct Sync<'a> {
+    children: Vec<Item>,
+    remote: &'a [Item],
+    /// The changes from the base snapshot to the local document.
+    local: Vec<Change>,
+    /// The folders renamed in the local children, by their path in the base snapshot.
+    renames: Vec<(Vec<String>, String)>,
+    report: SyncReport,
+}
+
+impl<'a> Sync<'a> {
+    fn conflict(&mut self, conflict: Conflict) {
+        if !self.report.conflicts.contains(&conflict) {
+            self.report.conflicts.push(conflict);
+        }
+    }
+
+    /// Rewrites a path of the base snapshot with the folder names of the local children.
+    fn local_path(&self, path: &[String]) -> Vec<String> {
+        let mut local = path.to_vec();
+
+        for (renamed, title) in self.renames.iter() {
+            if path.starts_with(renamed) {
+                local[renamed.len() - 1] = title.clone();
+            }
+        }
+
+        local
+    }
+
+    /// Lists the local changes of a bookmark of the base snapshot, given with its base folder.
+    fn local_changes(&self, href: &str, folder: &[String]) -> Vec<Change> {
+        let key = url::normalize(href);
+
+        self.local
+            .iter()
+            .filter(|change| changed_href(change).map(url::normalize) == Some(key.clone()))
+            .filter(|change| {
+                let base = match change {
+                    Change::BookmarkMoved { from, .. } => from,
+                    Change::BookmarkRenamed { folder, .. }
+                    | Change::BookmarkRemoved { folder, .. }
+                    | Change::HrefChanged { folder, .. } => folder,
+                    _ => return false,
+                };
+
+                base == folder
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Finds a bookmark of the base snapshot in the local children, even if its `href` changed.
+    ///
+    /// It is searched in its base folder only, the whole tree is searched when it was moved locally.
+    fn find_local(&self, href: &str, folder: &[String]) -> Option<Vec<usize>> {
+        let changes = self.local_changes(href, folder);
+        let key = changes
+            .iter()
+            .find_map(|change| match change {
+                Change::HrefChanged { to, .. } => Some(url::normalize(to)),
+                _ => None,
+            })
+            .unwrap_or_else(|| url::normalize(href));
+
+        match find_bookmark_in(&self.children, &self.local_path(folder), &key) {
+            Some(index) => Some(index),
+            None if changes
+                .iter()
+                .any(|change| matches!(change, Change::BookmarkMoved { .. })) =>
+            {
+                find_bookmark(&self.children, &key)
+            }
+            None => None,
+        }
+    }
+
+    /// Gets the local children of a folder of the base snapshot, it is created when it's missing.
+    fn folder(&mut self, path: &[String]) -> Option<&mut Vec<Item>> {
+        let local = self.local_path(path);
+
+        if find_folder(&self.children, &local).is_none() {
+            let removed = self.local.iter().find_map(|change| match change {
+                Change::FolderRemoved { path: removed } if path.starts_with(removed) => {
+                    Some(removed.clone())
+                }
+                _ => None,
+            });
+
+            if let Some(removed) = removed {
+                self.conflict(Conflict::FolderRemoved { path: removed });
+            }
+        }
+
+        ensure_folder(&mut self.children, &local)
+    }
+
+    /// Puts back a bookmark removed locally but changed remotely.
+    fn restore(&mut self, folder: &[String], href: &str) -> bool {
+        let remote = self.remote;
+        let bookmark = find_bookmark(remote, &url::normalize(href))
+            .and_then(|index| tree::get(remote, &index))
+            .and_then(Item::take_shortcut);
+
+        match bookmark {
+            Some(bookmark) => {
+                self.conflict(Conflict::BookmarkRemoved {
+                    folder: folder.to_vec(),
+                    bookmark: Box::new(bookmark.clone()),
+                });
+
+                if self.find_local(href, folder).is_some() {
+                    return false;
+                }
+
+                match self.folder(folder) {
+                    Some(list) => {
+                        list.push(Item::Shortcut(bookmark.clone()));
+                        true
+                    }
+                    None => false,
+                }
+            }
+            None => false,
+        }
+    }
+
+    fn rename_folder(&mut self, path: &[String], to: &str) -> bool {
+        let local = self.local.iter().find_map(|change| match change {
+            Change::FolderRenamed { path: renamed, to } if renamed == path => Some(to.clone()),
+            _ => None,
+        });
+
+        if let Some(local) = local {
+            if local != to {
+                self.conflict(Conflict::FolderRenamed {
+                    path: path.to_vec(),
+                    local,
+                    remote: to.to_string(),
+                });
+            }
+
+            return false;
+        }
+
+        let local_path = self.local_path(path);
+        let folder = find_folder(&self.children, &local_path)
+            .and_then(|index| tree::get_mut(&mut self.children, &index))
+            .and_then(Item::as_subfolder_mut);
+
+        match folder {
+            Some(folder) => {
+                folder.title = to.to_string();
+                self.renames.push((path.to_vec(), to.to_string()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn add_folder(&mut self, path: &[String], remote_path: &[String]) -> bool {
+        let local_path = self.local_path(path);
+
+        if find_folder(&self.children, &local_path).is_some() {
+            return false;
+        }
+
+        let remote = self.remote;
+        let folder = find_folder(remote, remote_path)
+            .and_then(|index| tree::get(remote, &index))
+            .and_then(Item::take_subfolder);
+
+        match (folder, path.split_last()) {
+            (Some(folder), Some((_, parent))) => {
+                // the bookmarks and the subfolders are added by their own changes
+                let mut added = folder.clone();
+                added
+                    .children
+                    .retain(|item| !item.is_shortcut() && !item.is_subfolder());
+
+                match self.folder(parent) {
+                    Some(list) => {
+                        list.push(Item::Subfolder(added));
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn remove_folder(&mut self, path: &[String]) -> bool {
+        let local_path = self.local_path(path);
+
+        match find_folder(&self.children, &local_path) {
+            Some(index) => tree::remove(&mut self.children, &index).is_ok(),
+            None => false,
+        }
+    }
+
+    fn add_bookmark(&mut self, folder: &[String], bookmark: &Bookmark) -> bool {
+        if find_bookmark(&self.children, &url::normalize(&bookmark.href)).is_some() {
+            return false;
+        }
+
+        match self.folder(folder) {
+            Some(list) => {
+                list.push(Item::Shortcut(bookmark.clone()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remove_bookmark(&mut self, folder: &[String], bookmark: &Bookmark) -> bool {
+        let changes = self.local_changes(&bookmark.href, folder);
+        let index = self.find_local(&bookmark.href, folder);
+
+        if changes
+            .iter()
+            .any(|change| !matches!(change, Change::BookmarkRemoved { .. }))
+        {
+            let kept = index
+                .and_then(|index| tree::get(&self.children, &index))
+                .and_then(Item::take_shortcut)
+                .cloned();
+
+            if let Some(kept) = kept {
+                self.conflict(Conflict::BookmarkRemoved {
+                    folder: folder.to_vec(),
+                    bookmark: Box::new(kept),
+                });
+            }
+
+            return false;
+        }
+
+        match index {
+            Some(index) => tree::remove(&mut self.children, &index).is_ok(),
+            None => false,
+        }
+    }
+
+    fn move_bookmark(&mut self, from: &[String], to: &[String], bookmark: &Bookmark) -> bool {
+        let changes = self.local_changes(&bookmark.href, from);
+
+        if changes
+            .iter()
+            .any(|change| matches!(change, Change::BookmarkRemoved { .. }))
+        {
+            return self.restore(to, &bookmark.href);
+        }
+
+        let local = changes.into_iter().find_map(|change| match change {
+            Change::BookmarkMoved { to, .. } => Some(to),
+            _ => None,
+        });
+
+        if let Some(local) = local {
+            if local != to {
+                self.conflict(Conflict::BookmarkMoved {
+                    href: bookmark.href.clone(),
+                    local,
+                    remote: to.to_vec(),
+                });
+            }
+
+            return false;
+        }
+
+        // the folder is created first, since it would shift the indexes of the bookmark otherwise
+        if self.folder(to).is_none() {
+            return false;
+        }
+
+        let item = self
+            .find_local(&bookmark.href, from)
+            .and_then(|index| tree::remove(&mut self.children, &index).ok());
+
+        match (item, self.folder(to)) {
+            (Some(item), Some(list)) => {
+                list.push(item);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn rename_bookmark(&mut self, folder: &[String], href: &str, to: &str) -> bool {
+        let changes = self.local_changes(href, folder);
+
+        if changes
+            .iter()
+            .any(|change| matches!(change, Change::BookmarkRemoved { .. }))
+        {
+            return self.restore(folder, href);
+        }
+
+        let local = changes.into_iter().find_map(|change| match change {
+            Change::BookmarkRenamed { to, .. } => Some(to),
+            _ => None,
+        });
+
+        if let Some(local) = local {
+            if local != to {
+                self.conflict(Conflict::BookmarkRenamed {
+                    href: href.to_string(),
+                    local,
+                    remote: to.to_string(),
+                });
+            }
+
+            return false;
+        }
+
+        let bookmark = self
+            .find_local(href, folder)
+            .and_then(|index| tree::get_mut(&mut self.children, &index))
+            .and_then(Item::as_shortcut_mut);
+
+        match bookmark {
+            Some(bookmark) => {
+                bookmark.title = to.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn change_href(&mut self, folder: &[String], from: &str, to: &str) -> bool {
+        let changes = self.local_changes(from, folder);
+
+        if changes
+            .iter()
+            .any(|change| matches!(change, Change::BookmarkRemoved { .. }))
+        {
+            return self.restore(folder, to);
+        }
+
+        let local = changes.into_iter().find_map(|change| match change {
+            Change::HrefChanged { to, .. } => Some(to),
+            _ => None,
+        });
+
+        if let Some(local) = local {
+            if url::normalize(&local) != url::normalize(to) {
+                self.conflict(Conflict::HrefChanged {
+                    href: from.to_string(),
+                    local,
+                    remote: to.to_string(),
+                });
+            }
+
+            return false;
+        }
+
+        let bookmark = self
+            .find_local(from, folder)
+            .and_then(|index| tree::get_mut(&mut self.children, &index))
+            .and_then(Item::as_shortcut_mut);
+
+        match bookmark {
+            Some(bookmark) => {
+                bookmark.href = to.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Lists the feeds, the web slices and the separators with the titles of their folders.
+fn others(children: &[Item]) -> Vec<(Vec<String>, &Item)> {
+    Iter::new(children)
+        .filter(|(_, _, item)| matches!(item, Item::Feed(_) | Item::WebSlice(_) | Item::Separator))
+        .map(|(_, parent, item)| {
+            let folder = parent.segments.into_iter().map(|s| s.title).collect();
+            (folder, item)
+        })
+        .collect()
+}
+
+/// Finds the feeds, the web slices and the separators added or removed by the remote document,
+/// the folders renamed by the remote document are compared with their base titles.
+fn unapplied(base: &[Item], remote: &[Item], renames: &[(Vec<String>, String)]) -> Vec<Unapplied> {
+    let mut removed = others(base);
+    let mut unapplied = vec![];
+
+    for (folder, item) in others(remote) {
+        let base_folder = base_path(&folder, renames);
+
+        match removed
+            .iter()
+            .position(|(path, other)| *path == base_folder && *other == item)
+        {
+            Some(position) => {
+                removed.remove(position);
+            }
+            None => unapplied.push(Unapplied {
+                folder,
+                item: item.clone(),
+                removed: false,
+            }),
+        }
+    }
+
+    unapplied.extend(removed.into_iter().map(|(folder, item)| Unapplied {
+        folder,
+        item: item.clone(),
+        removed: true,
+    }));
+    unapplied
+}
+
+/// Applies the changes from the base children to the remote children on a copy of the local children.
+pub(crate) fn sync(base: &[Item], local: &[Item], remote: &[Item]) -> (Vec<Item>, SyncReport) {
+    let local_changes = diff::diff(base, local).changes;
+    let remote_changes = diff::diff(base, remote).changes;
+
+    let renames = |changes: &[Change]| {
+        changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::FolderRenamed { path, to } => Some((path.clone(), to.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let local_renames = renames(&local_changes);
+    let remote_renames = renames(&remote_changes);
+    let mut sync = Sync {
+        children: local.to_vec(),
+        remote,
+        local: local_changes
+            .into_iter()
+            .map(|change| to_base(change, &local_renames))
+            .collect(),
+        renames: local_renames,
+        report: SyncReport {
+            unapplied: unapplied(base, remote, &remote_renames),
+            ..SyncReport::default()
+        },
+    };
+
+    // a folder changed locally is kept with all its bookmarks
+    let mut kept: Vec<Vec<String>> = vec![];
+
+    for change in remote_changes.iter() {
+        if let Change::FolderRemoved { path } = change {
+            let touched = sync.local.iter().any(|local| touches(local, path));
+
+            if touched {
+                kept.push(path.clone());
+                sync.conflict(Conflict::FolderRemoved { path: path.clone() });
+            }
+        }
+    }
+
+    // the folders are renamed and added first, so the bookmarks can be put in them,
+    // and they are removed last, once their bookmarks are moved away
+    for change in remote_changes.iter() {
+        let applied = match change {
+            Change::FolderRenamed { path, to } => sync.rename_folder(path, to),
+            Change::FolderAdded { path } => {
+                sync.add_folder(&base_path(path, &remote_renames), path)
+            }
+            _ => false,
+        };
+
+        if applied {
+            sync.report.applied.push(change.clone());
+        }
+    }
+
+    // the bookmarks are renamed before they are moved, so they are still found in their base folders
+    for change in remote_changes.iter() {
+        let applied = match change {
+            Change::BookmarkRenamed {
+                folder, href, to, ..
+            } => sync.rename_bookmark(folder, href, to),
+            Change::HrefChanged {
+                folder, from, to, ..
+            } => sync.change_href(folder, from, to),
+            _ => false,
+        };
+
+        if applied {
+            sync.report.applied.push(change.clone());
+        }
+    }
+
+    for change in remote_changes.iter() {
+        let applied = match change {
+            Change::BookmarkAdded { folder, bookmark } => {
+                sync.add_bookmark(&base_path(folder, &remote_renames), bookmark)
+            }
+            Change::BookmarkRemoved { folder, bookmark } => {
+                !kept.iter().any(|path| folder.starts_with(path))
+                    && sync.remove_bookmark(folder, bookmark)
+            }
+            Change::BookmarkMoved { from, to, bookmark } => {
+                sync.move_bookmark(from, &base_path(to, &remote_renames), bookmark)
+            }
+            _ => false,
+        };
+
+        if applied {
+            sync.report.applied.push(change.clone());
+        }
+    }
+
+    for change in remote_changes.iter() {
+        if let Change::FolderRemoved { path } = change {
+            if !kept.contains(path) && sync.remove_folder(path) {
+                sync.report.applied.push(change.clone());
+            }
+        }
+    }
+
+    (sync.children, sync.report)
+}
+
+#[allow(dead_code)]
+fn bookmark(href: &str, title: &str) -> Item {
+    use crate::bookmark::BookmarkBuilder;
+
+    Item::Shortcut(
+        BookmarkBuilder::default()
+            .href(href)
+            .title(title)
+            .build()
+            .unwrap(),
+    )
+}
+
+#[allow(dead_code)]
+fn folder(title: &str, children: Vec<Item>) -> Item {
+    use crate::folder::FolderBuilder;
+
+    Item::Subfolder(
+        FolderBuilder::default()
+            .title(title)
+            .children(children)
+            .build()
+            .unwrap(),
+    )
+}
+
+#[test]
+fn should_sync_independent_changes() {
+    let base = vec![
+        bookmark("https://a.com", "a"),
+        folder("dev", vec![bookmark("https://b.com", "b")]),
+        folder("old", vec![bookmark("https://c.com", "c")]),
+    ];
+    let local = vec![
+        bookmark("https://a.com", "a"),
+        folder(
+            "development",
+            vec![
+                bookmark("https://b.com", "b"),
+                bookmark("https://d.com", "d"),
+            ],
+        ),
+        folder("old", vec![bookmark("https://c.com", "c")]),
+    ];
+    let remote = vec![
+        bookmark("https://a.com", "renamed a"),
+        folder(
+            "dev",
+            vec![
+                bookmark("https://b.com", "b"),
+                bookmark("https://e.com", "e"),
+            ],
+        ),
+    ];
+
+    let (children, report) = sync(&base, &local, &remote);
+
+    assert_eq!(
+        children,
+        vec![
+            bookmark("https://a.com", "renamed a"),
+            folder(
+                "development",
+                vec![
+                    bookmark("https://b.com", "b"),
+                    bookmark("https://d.com", "d"),
+                    bookmark("https://e.com", "e"),
+                ],
+            ),
+        ]
+    );
+    assert_eq!(report.applied.len(), 4);
+    assert!(!report.has_conflicts());
+    assert_eq!(sync(&base, &base, &base).1, SyncReport::default());
+}
+
+#[test]
+fn should_report_sync_conflicts() {
+    let base = vec![
+        bookmark("https://a.com", "a"),
+        bookmark("https://b.com", "b"),
+        folder("f", vec![bookmark("https://c.com", "c")]),
+    ];
+    let local = vec![
+        bookmark("https://a.com", "local a"),
+        folder("f", vec![bookmark("https://c.com", "local c")]),
+    ];
+    let remote = vec![
+        bookmark("https://a.com", "remote a"),
+        bookmark("https://b.com", "remote b"),
+    ];
+
+    let (children, report) = sync(&base, &local, &remote);
+
+    assert_eq!(
+        children,
+        vec![
+            bookmark("https://a.com", "local a"),
+            folder("f", vec![bookmark("https://c.com", "local c")]),
+            bookmark("https://b.com", "remote b"),
+        ]
+    );
+    assert_eq!(
+        report.conflicts,
+        vec![
+            Conflict::FolderRemoved {
+                path: vec![String::from("f")]
+            },
+            Conflict::BookmarkRenamed {
+                href: String::from("https://a.com"),
+                local: String::from("local a"),
+                remote: String::from("remote a"),
+            },
+            Conflict::BookmarkRemoved {
+                folder: vec![],
+                bookmark: Box::new(
+                    bookmark("https://b.com", "remote b")
+                        .into_shortcut()
+                        .unwrap()
+                ),
+            },
+        ]
+    );
+}
+
+#[test]
+fn should_report_unapplied_items() {
+    use crate::feed::FeedBuilder;
+
+    let feed = Item::Feed(
+        FeedBuilder::default()
+            .feed_url("https://a.com/feed.xml")
+            .title("feed")
+            .build()
+            .unwrap(),
+    );
+    let base = vec![
+        folder("f", vec![bookmark("https://a.com", "a"), Item::Separator]),
+        Item::Separator,
+    ];
+    let remote = vec![
+        folder(
+            "g",
+            vec![
+                bookmark("https://a.com", "a"),
+                Item::Separator,
+                feed.clone(),
+            ],
+        ),
+        bookmark("https://b.com", "b"),
+    ];
+
+    let (children, report) = sync(&base, &base, &remote);
+
+    assert_eq!(
+        children,
+        vec![
+            folder("g", vec![bookmark("https://a.com", "a"), Item::Separator]),
+            Item::Separator,
+            bookmark("https://b.com", "b"),
+        ]
+    );
+    assert_eq!(
+        report.unapplied,
+        vec![
+            Unapplied {
+                folder: vec![String::from("g")],
+                item: feed,
+                removed: false,
+            },
+            Unapplied {
+                folder: vec![],
+                item: Item::Separator,
+                removed: true,
+            },
+        ]
+    );
+}
+
+#[test]
+fn should_sync_duplicated_bookmarks() {
+    let base = vec![
+        folder("A", vec![bookmark("https://x.com/", "x")]),
+        folder("B", vec![bookmark("https://x.com/", "x")]),
+    ];
+    let remote = vec![
+        folder("A", vec![]),
+        folder("B", vec![bookmark("https://x.com/", "renamed x")]),
+    ];
+
+    let (children, report) = sync(&base, &base, &remote);
+
+    assert_eq!(children, remote);
+    assert_eq!(
+        report.applied,
+        vec![
+            Change::BookmarkRenamed {
+                folder: vec![String::from("B")],
+                href: String::from("https://x.com/"),
+                from: String::from("x"),
+                to: String::from("renamed x"),
+            },
+            Change::BookmarkRemoved {
+                folder: vec![String::from("A")],
+                bookmark: bookmark("https://x.com/", "x").into_shortcut().unwrap(),
+            },
+        ]
+    );
+
+    let local = vec![
+        folder("A", vec![bookmark("https://x.com/", "local x")]),
+        folder("B", vec![bookmark("https://x.com/", "x")]),
+    ];
+
+    let (children, report) = sync(&base, &local, &remote);
+
+    assert_eq!(
+        children,
+        vec![
+            folder("A", vec![bookmark("https://x.com/", "local x")]),
+            remote[1].clone()
+        ]
+    );
+    assert_eq!(report.applied.len(), 1);
+}
+
+#[test]
+fn should_sync_additions_in_renamed_folders() {
+    let base = vec![folder(
+        "Old",
+        vec![
+            bookmark("https://x.com", "x"),
+            bookmark("https://z.com", "z"),
+        ],
+    )];
+    let remote = vec![folder(
+        "New",
+        vec![
+            bookmark("https://x.com", "x"),
+            folder(
+                "Sub",
+                vec![
+                    bookmark("https://y.com", "y"),
+                    bookmark("https://z.com", "z"),
+                ],
+            ),
+        ],
+    )];
+
+    let (children, report) = sync(&base, &base, &remote);
+
+    assert_eq!(
+        children,
+        vec![folder(
+            "New",
+            vec![
+                bookmark("https://x.com", "x"),
+                folder(
+                    "Sub",
+                    vec![
+                        bookmark("https://z.com", "z"),
+                        bookmark("https://y.com", "y"),
+                    ],
+                ),
+            ],
+        )]
+    );
+    assert_eq!(report.applied.len(), 4);
+    assert!(!report.has_conflicts());
+}
@@ -0,0 +1,170 @@
+//! Contains the [Timestamp] model and its associated tests.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Represents the precision of a [Timestamp], as guessed from its magnitude.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precision {
+    /// The timestamp counts the seconds since the UNIX epoch, as specified by the Netscape format.
+    Seconds,
+
+    /// The timestamp counts the milliseconds since the UNIX epoch.
+    Milliseconds,
+
+    /// The timestamp counts the microseconds since the UNIX epoch, as done by some Firefox exports.
+    Microseconds,
+}
+
+/// Implements the dates of the items (`ADD_DATE`, `LAST_VISIT` and `LAST_MODIFIED`).
+///
+/// The [Timestamp] keeps the original text of the attribute, so a rendered document stays identical
+/// to the parsed one, even if the date is empty or malformed.
+/// The value is interpreted lazily as a UNIX time in seconds, milliseconds or microseconds.
+///
+/// ```rust
+/// use bookmarkt::Timestamp;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let seconds = Timestamp::from("1600910474");
+/// let micros = Timestamp::from("1600910474000000");
+///
+/// assert_eq!(seconds.to_system_time(), micros.to_system_time());
+/// assert_eq!(seconds.to_system_time(), Some(UNIX_EPOCH + Duration::from_secs(1600910474)));
+/// assert_eq!(micros.to_string(), "1600910474000000");
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct Timestamp(String);
+
+impl Timestamp {
+    /// Gets the original text of the timestamp.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Checks if the timestamp has no text at all.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Guesses the [Precision] of the timestamp, it returns [None] if it isn't a number.
+    pub fn precision(&self) -> Option<Precision> {
+        self.value().map(|value| {
+            if value < 100_000_000_000 {
+                Precision::Seconds
+            } else if value < 100_000_000_000_000 {
+                Precision::Milliseconds
+            } else {
+                Precision::Microseconds
+            }
+        })
+    }
+
+    /// Gets the number of microseconds since the UNIX epoch.
+    pub fn as_micros(&self) -> Option<u64> {
+        let value = self.value()?;
+
+        match self.precision()? {
+            Precision::Seconds => value.checked_mul(1_000_000),
+            Precision::Milliseconds => value.checked_mul(1_000),
+            Precision::Microseconds => Some(value),
+        }
+    }
+
+    /// Gets the number of seconds since the UNIX epoch.
+    pub fn as_secs(&self) -> Option<u64> {
+        self.as_micros().map(|micros| micros / 1_000_000)
+    }
+
+    /// Converts the timestamp to a [SystemTime], it returns [None] if the text isn't a valid date.
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        self.as_micros()
+            .and_then(|micros| UNIX_EPOCH.checked_add(Duration::from_micros(micros)))
+    }
+
+    fn value(&self) -> Option<u64> {
+        let trimmed = self.0.trim();
+
+        if !trimmed.is_empty() && trimmed.bytes().all(|b| b.is_ascii_digit()) {
+            trimmed.parse().ok()
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for Timestamp {
+    fn from(raw: String) -> Self {
+        Timestamp(raw)
+    }
+}
+
+impl From<&str> for Timestamp {
+    fn from(raw: &str) -> Self {
+        Timestamp(String::from(raw))
+    }
+}
+
+/// Creates a [Timestamp] in seconds, the dates before the UNIX epoch are clamped to it.
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        let seconds = time
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        Timestamp(seconds.to_string())
+    }
+}
+
+#[test]
+fn should_guess_timestamp_precision() {
+    assert_eq!(
+        Timestamp::from("1466009059").precision(),
+        Some(Precision::Seconds)
+    );
+    assert_eq!(
+        Timestamp::from("1466009059123").precision(),
+        Some(Precision::Milliseconds)
+    );
+    assert_eq!(
+        Timestamp::from("1466009059123456").precision(),
+        Some(Precision::Microseconds)
+    );
+}
+
+#[test]
+fn should_convert_timestamp_to_system_time() {
+    let expected = UNIX_EPOCH + Duration::from_secs(1466009059);
+
+    assert_eq!(
+        Timestamp::from("1466009059").to_system_time(),
+        Some(expected)
+    );
+    assert_eq!(
+        Timestamp::from("1466009059000").to_system_time(),
+        Some(expected)
+    );
+    assert_eq!(
+        Timestamp::from("1466009059000000").to_system_time(),
+        Some(expected)
+    );
+    assert_eq!(Timestamp::from(expected), Timestamp::from("1466009059"));
+}
+
+#[test]
+fn should_keep_malformed_timestamp() {
+    for raw in ["", "date", "-1", "12.5"].iter() {
+        let timestamp = Timestamp::from(*raw);
+
+        assert_eq!(timestamp.to_system_time(), None);
+        assert_eq!(timestamp.to_string(), *raw);
+    }
+}
@@ -0,0 +1,397 @@
+//! Contains the lazy traversals of the items, with their depth and the [BookmarkPath] of their parent folder.
+//!
+//! The top-level items have a depth of `0` and an empty parent path.
+use std::collections::HashMap;
+use std::slice;
+
+use crate::bookmark_path::{BookmarkPath, Segment};
+use crate::item::Item;
+use crate::Folder;
+
+/// Counts the titles already seen in a list, to build the segments of the same-named siblings.
+fn segment<K: Into<String> + std::hash::Hash + Eq + Clone>(
+    titles: &mut HashMap<K, usize>,
+    title: K,
+) -> Segment {
+    let count = titles.entry(title.clone()).or_default();
+    let segment = Segment::new(title, *count);
+
+    *count += 1;
+    segment
+}
+
+/// Iterates over the items, depth first and in the document order.
+///
+/// It yields `(depth, parent path, &Item)`, a folder is yielded before its children.
+pub struct Iter<'a> {
+    /// The children being iterated at each depth, with the count of the titles already seen.
+    stack: Vec<(slice::Iter<'a, Item>, HashMap<&'a str, usize>)>,
+    prefix: Vec<Segment>,
+}
+
+impl<'a> Iter<'a> {
+    pub(crate) fn new(children: &'a [Item]) -> Self {
+        Iter {
+            stack: vec![(children.iter(), HashMap::new())],
+            prefix: vec![],
+        }
+    }
+
+    /// Gets the next item with the segment addressing it in its parent, a separator has no segment.
+    pub(crate) fn next_entry(
+        &mut self,
+    ) -> Option<(usize, BookmarkPath, Option<Segment>, &'a Item)> {
+        loop {
+            let depth = self.stack.len().saturating_sub(1);
+            let (items, titles) = self.stack.last_mut()?;
+
+            let item = match items.next() {
+                Some(item) => item,
+                None => {
+                    self.stack.pop();
+                    self.prefix.pop();
+                    continue;
+                }
+            };
+
+            let segment = item.title().map(|title| segment(titles, title));
+            let parent = BookmarkPath {
+                segments: self.prefix.clone(),
+            };
+
+            if let (Item::Subfolder(folder), Some(segment)) = (item, &segment) {
+                self.stack.push((folder.children.iter(), HashMap::new()));
+                self.prefix.push(segment.clone());
+            }
+
+            return Some((depth, parent, segment, item));
+        }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (usize, BookmarkPath, &'a Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, parent, _, item) = self.next_entry()?;
+
+        Some((depth, parent, item))
+    }
+}
+
+/// Iterates mutably over the items that aren't folders, depth first and in the document order.
+///
+/// It yields `(depth, parent path, &mut Item)`. The folders aren't yielded, since their children are
+/// borrowed by the iterator : a [VisitorMut] edits the folders.
+pub struct IterMut<'a> {
+    /// The children being iterated at each depth, with the count of the titles already seen.
+    stack: Vec<(slice::IterMut<'a, Item>, HashMap<String, usize>)>,
+    prefix: Vec<Segment>,
+}
+
+impl<'a> IterMut<'a> {
+    pub(crate) fn new(children: &'a mut [Item]) -> Self {
+        IterMut {
+            stack: vec![(children.iter_mut(), HashMap::new())],
+            prefix: vec![],
+        }
+    }
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (usize, BookmarkPath, &'a mut Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let depth = self.stack.len().saturating_sub(1);
+            let (items, titles) = self.stack.last_mut()?;
+
+            let item = match items.next() {
+                Some(item) => item,
+                None => {
+                    self.stack.pop();
+                    self.prefix.pop();
+                    continue;
+                }
+            };
+
+            if let Some(title) = item.title() {
+                // the segment is only needed to enter a folder, but it counts the other siblings too
+                let segment = segment(titles, title.to_string());
+
+                if let Item::Subfolder(folder) = item {
+                    self.stack
+                        .push((folder.children.iter_mut(), HashMap::new()));
+                    self.prefix.push(segment);
+                    continue;
+                }
+            }
+
+            let parent = BookmarkPath {
+                segments: self.prefix.clone(),
+            };
+
+            return Some((depth, parent, item));
+        }
+    }
+}
+
+/// Visits the items of a document, depth first and in the document order, see [Netscape::walk].
+///
+/// All the hooks do nothing by default, they get the depth and the path of the parent folder.
+///
+/// ```rust
+/// use bookmarkt::{BookmarkPath, Folder, Netscape, Visitor};
+/// use std::path::Path;
+///
+/// #[derive(Default)]
+/// struct Outline(String);
+///
+/// impl Visitor for Outline {
+///     fn enter_folder(&mut self, depth: usize, _: &BookmarkPath, folder: &Folder) {
+///         self.0.push_str(&format!("{}{}\n", "  ".repeat(depth), folder.title));
+///     }
+/// }
+///
+/// let chromium = Netscape::from_file(Path::new("./res/chromium.html")).unwrap();
+/// let mut outline = Outline::default();
+/// chromium.walk(&mut outline);
+///
+/// assert_eq!(outline.0, "Bookmarks bar\nReferences\nDependencies\n");
+/// ```
+///
+/// [Netscape::walk]: crate::Netscape::walk
+pub trait Visitor {
+    /// Visits a folder before its children.
+    fn enter_folder(&mut self, _depth: usize, _parent: &BookmarkPath, _folder: &Folder) {}
+
+    /// Visits a folder after its children.
+    fn leave_folder(&mut self, _depth: usize, _parent: &BookmarkPath, _folder: &Folder) {}
+
+    /// Visits an item that isn't a folder.
+    fn visit_item(&mut self, _depth: usize, _parent: &BookmarkPath, _item: &Item) {}
+}
+
+/// Visits and edits the items of a document, depth first and in the document order, see [Netscape::walk_mut].
+///
+/// The children of a folder are visited once [VisitorMut::enter_folder] returns, so they can be edited there
+/// or in [VisitorMut::leave_folder]. The parent paths are built from the titles before they are visited.
+///
+/// ```rust
+/// use bookmarkt::{BookmarkPath, Folder, Item, Netscape, VisitorMut};
+/// use std::collections::HashSet;
+/// use std::path::Path;
+///
+/// /// Removes the bookmarks already in the same folder.
+/// struct Dedupe;
+///
+/// impl VisitorMut for Dedupe {
+///     fn leave_folder(&mut self, _: usize, _: &BookmarkPath, folder: &mut Folder) {
+///         let mut seen = HashSet::new();
+///
+///         folder.children.retain(|item| match item {
+///             Item::Shortcut(bookmark) => seen.insert(bookmark.href.clone()),
+///             _ => true,
+///         });
+///     }
+/// }
+///
+/// let mut chromium = Netscape::from_file(Path::new("./res/chromium.html")).unwrap();
+/// let count = chromium.get_bookmarks().len();
+/// chromium.walk_mut(&mut Dedupe);
+///
+/// assert_eq!(chromium.get_bookmarks().len(), count);
+/// ```
+///
+/// [Netscape::walk_mut]: crate::Netscape::walk_mut
+pub trait VisitorMut {
+    /// Visits a folder before its children.
+    fn enter_folder(&mut self, _depth: usize, _parent: &BookmarkPath, _folder: &mut Folder) {}
+
+    /// Visits a folder after its children.
+    fn leave_folder(&mut self, _depth: usize, _parent: &BookmarkPath, _folder: &mut Folder) {}
+
+    /// Visits an item that isn't a folder.
+    fn visit_item(&mut self, _depth: usize, _parent: &BookmarkPath, _item: &mut Item) {}
+}
+
+/// Walks the children with a [Visitor].
+pub(crate) fn walk<V: Visitor + ?Sized>(
+    children: &[Item],
+    depth: usize,
+    parent: &mut BookmarkPath,
+    visitor: &mut V,
+) {
+    let mut titles = HashMap::new();
+
+    for item in children.iter() {
+        let segment = item.title().map(|title| segment(&mut titles, title));
+
+        match (item, segment) {
+            (Item::Subfolder(folder), Some(segment)) => {
+                visitor.enter_folder(depth, parent, folder);
+
+                parent.segments.push(segment);
+                walk(&folder.children, depth + 1, parent, visitor);
+                parent.segments.pop();
+
+                visitor.leave_folder(depth, parent, folder);
+            }
+            _ => visitor.visit_item(depth, parent, item),
+        }
+    }
+}
+
+/// Walks the children with a [VisitorMut].
+pub(crate) fn walk_mut<V: VisitorMut + ?Sized>(
+    children: &mut [Item],
+    depth: usize,
+    parent: &mut BookmarkPath,
+    visitor: &mut V,
+) {
+    let mut titles = HashMap::new();
+
+    for item in children.iter_mut() {
+        let segment = item
+            .title()
+            .map(|title| segment(&mut titles, title.to_string()));
+
+        match (item, segment) {
+            (Item::Subfolder(folder), Some(segment)) => {
+                visitor.enter_folder(depth, parent, folder);
+
+                parent.segments.push(segment);
+                walk_mut(&mut folder.children, depth + 1, parent, visitor);
+                parent.segments.pop();
+
+                visitor.leave_folder(depth, parent, folder);
+            }
+            (item, _) => visitor.visit_item(depth, parent, item),
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn mock_tree() -> Vec<Item> {
+    use crate::bookmark::BookmarkBuilder;
+    use crate::folder::FolderBuilder;
+
+    let bookmark = |name: &str| {
+        Item::Shortcut(
+            BookmarkBuilder::default()
+                .href(name)
+                .title(name)
+                .build()
+                .unwrap(),
+        )
+    };
+
+    let folder = |name: &str, children: Vec<Item>| {
+        Item::Subfolder(
+            FolderBuilder::default()
+                .title(name)
+                .children(children)
+                .build()
+                .unwrap(),
+        )
+    };
+
+    vec![
+        bookmark("a"),
+        folder("f", vec![bookmark("b"), folder("f", vec![bookmark("c")])]),
+        Item::Separator,
+        folder("f", vec![]),
+    ]
+}
+
+#[test]
+fn should_iterate_with_depth_and_parent() {
+    let tree = mock_tree();
+    let entries = Iter::new(&tree)
+        .map(|(depth, parent, item)| (depth, parent.to_string(), item.title()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        entries,
+        vec![
+            (0, String::new(), Some("a")),
+            (0, String::new(), Some("f")),
+            (1, String::from("f"), Some("b")),
+            (1, String::from("f"), Some("f")),
+            (2, String::from("f/f"), Some("c")),
+            (0, String::new(), None),
+            (0, String::new(), Some("f")),
+        ]
+    );
+}
+
+#[test]
+fn should_iterate_mutably() {
+    let mut tree = mock_tree();
+
+    for (depth, parent, item) in IterMut::new(&mut tree) {
+        if let Some(bookmark) = item.as_shortcut_mut() {
+            bookmark.title = format!("{}/{}:{}", parent, bookmark.title, depth);
+        }
+    }
+
+    let titles = Iter::new(&tree)
+        .filter_map(|(_, _, item)| item.take_shortcut())
+        .map(|bookmark| bookmark.title.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(titles, vec!["/a:0", "f/b:1", "f/f/c:2"]);
+}
+
+#[test]
+fn should_walk_with_visitors() {
+    #[derive(Default)]
+    struct Trace(Vec<String>);
+
+    impl Visitor for Trace {
+        fn enter_folder(&mut self, depth: usize, parent: &BookmarkPath, folder: &Folder) {
+            self.0
+                .push(format!("enter {} {}/{}", depth, parent, folder.title));
+        }
+
+        fn leave_folder(&mut self, depth: usize, parent: &BookmarkPath, folder: &Folder) {
+            self.0
+                .push(format!("leave {} {}/{}", depth, parent, folder.title));
+        }
+
+        fn visit_item(&mut self, depth: usize, parent: &BookmarkPath, item: &Item) {
+            self.0
+                .push(format!("visit {} {}/{:?}", depth, parent, item.title()));
+        }
+    }
+
+    struct Rename;
+
+    impl VisitorMut for Rename {
+        fn enter_folder(&mut self, depth: usize, _: &BookmarkPath, folder: &mut Folder) {
+            folder.title = format!("{}{}", folder.title, depth);
+        }
+    }
+
+    let mut tree = mock_tree();
+    walk_mut(&mut tree, 0, &mut BookmarkPath::new(), &mut Rename);
+
+    let mut trace = Trace::default();
+    walk(&tree, 0, &mut BookmarkPath::new(), &mut trace);
+
+    assert_eq!(
+        trace.0,
+        vec![
+            "visit 0 /Some(\"a\")",
+            "enter 0 /f0",
+            "visit 1 f0/Some(\"b\")",
+            "enter 1 f0/f1",
+            "visit 2 f0/f1/Some(\"c\")",
+            "leave 1 f0/f1",
+            "leave 0 /f0",
+            "visit 0 /None",
+            "enter 0 /f0",
+            "leave 0 /f0",
+        ]
+    );
+}
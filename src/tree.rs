@@ -0,0 +1,206 @@
+//! Contains the editing operations shared by the [Folder] and [Netscape] children.
+//!
+//! An item is addressed by a path of indexes : each index selects a child of the current list
+//! and the next index goes down into it, so all the indexes but the last one must select subfolders.
+//!
+//! [Folder]: crate::Folder
+//! [Netscape]: crate::Netscape
+use std::mem;
+
+use crate::item::Item;
+use crate::Error;
+
+/// Gets the list of children of the subfolder at the given path, the empty path is the root list.
+pub(crate) fn list_mut<'a>(
+    children: &'a mut Vec<Item>,
+    path: &[usize],
+) -> Option<&'a mut Vec<Item>> {
+    let mut list = children;
+
+    for index in path {
+        list = &mut list.get_mut(*index)?.as_subfolder_mut()?.children;
+    }
+
+    Some(list)
+}
+
+/// Gets the item at the given path.
+pub(crate) fn get<'a>(children: &'a [Item], path: &[usize]) -> Option<&'a Item> {
+    let (first, rest) = path.split_first()?;
+    let mut item = children.get(*first)?;
+
+    for index in rest {
+        item = item.take_subfolder()?.children.get(*index)?;
+    }
+
+    Some(item)
+}
+
+/// Gets the item at the given path mutably.
+pub(crate) fn get_mut<'a>(children: &'a mut Vec<Item>, path: &[usize]) -> Option<&'a mut Item> {
+    let (last, parents) = path.split_last()?;
+
+    list_mut(children, parents)?.get_mut(*last)
+}
+
+/// Inserts the item at the given path, the item is given back when the path is invalid.
+fn try_insert(children: &mut Vec<Item>, path: &[usize], item: Item) -> Option<Item> {
+    let list = path
+        .split_last()
+        .and_then(|(last, parents)| Some((*last, list_mut(children, parents)?)));
+
+    match list {
+        Some((index, list)) if index <= list.len() => {
+            list.insert(index, item);
+            None
+        }
+        _ => Some(item),
+    }
+}
+
+/// Inserts the item at the given path, the last index can be the length of the list to append the item.
+pub(crate) fn insert(children: &mut Vec<Item>, path: &[usize], item: Item) -> Result<(), Error> {
+    match try_insert(children, path, item) {
+        Some(_) => Err(Error::InvalidPath(path.to_vec())),
+        None => Ok(()),
+    }
+}
+
+/// Removes the item at the given path and returns it.
+pub(crate) fn remove(children: &mut Vec<Item>, path: &[usize]) -> Result<Item, Error> {
+    path.split_last()
+        .and_then(|(last, parents)| Some((*last, list_mut(children, parents)?)))
+        .filter(|(index, list)| *index < list.len())
+        .map(|(index, list)| list.remove(index))
+        .ok_or_else(|| Error::InvalidPath(path.to_vec()))
+}
+
+/// Replaces the item at the given path and returns the previous one.
+pub(crate) fn replace(children: &mut Vec<Item>, path: &[usize], item: Item) -> Result<Item, Error> {
+    get_mut(children, path)
+        .map(|current| mem::replace(current, item))
+        .ok_or_else(|| Error::InvalidPath(path.to_vec()))
+}
+
+/// Moves the item from a path to another one, the destination is resolved once the item is removed.
+///
+/// The tree is left untouched when one of the paths is invalid.
+pub(crate) fn move_item(
+    children: &mut Vec<Item>,
+    from: &[usize],
+    to: &[usize],
+) -> Result<(), Error> {
+    let item = remove(children, from)?;
+
+    match try_insert(children, to, item) {
+        Some(item) => {
+            // the item has just been removed from there, so it can be put back
+            try_insert(children, from, item);
+            Err(Error::InvalidPath(to.to_vec()))
+        }
+        None => Ok(()),
+    }
+}
+
+#[allow(dead_code)]
+fn mock_tree() -> Vec<Item> {
+    use crate::bookmark::BookmarkBuilder;
+    use crate::folder::FolderBuilder;
+
+    let bookmark = |name: &str| {
+        Item::Shortcut(
+            BookmarkBuilder::default()
+                .href(name)
+                .title(name)
+                .build()
+                .unwrap(),
+        )
+    };
+
+    let folder = |name: &str, children: Vec<Item>| {
+        Item::Subfolder(
+            FolderBuilder::default()
+                .title(name)
+                .children(children)
+                .build()
+                .unwrap(),
+        )
+    };
+
+    vec![
+        bookmark("a"),
+        folder("f", vec![bookmark("b"), folder("g", vec![bookmark("c")])]),
+    ]
+}
+
+#[allow(dead_code)]
+fn title(item: Option<&Item>) -> Option<&str> {
+    item.and_then(|item| match item {
+        Item::Shortcut(bookmark) => Some(bookmark.title.as_str()),
+        Item::Subfolder(folder) => Some(folder.title.as_str()),
+        _ => None,
+    })
+}
+
+#[test]
+fn should_get_items_by_path() {
+    let mut tree = mock_tree();
+
+    assert_eq!(title(get(&tree, &[0])), Some("a"));
+    assert_eq!(title(get(&tree, &[1, 1, 0])), Some("c"));
+    assert_eq!(title(get(&tree, &[0, 0])), None);
+    assert_eq!(title(get(&tree, &[2])), None);
+    assert_eq!(title(get(&tree, &[])), None);
+
+    get_mut(&mut tree, &[1, 0])
+        .and_then(Item::as_shortcut_mut)
+        .unwrap()
+        .title = String::from("renamed");
+    assert_eq!(title(get(&tree, &[1, 0])), Some("renamed"));
+}
+
+#[test]
+fn should_insert_and_remove_items_by_path() {
+    let mut tree = mock_tree();
+
+    insert(&mut tree, &[1, 1, 1], Item::Separator).unwrap();
+    assert!(get(&tree, &[1, 1, 1]).unwrap().is_separator());
+
+    assert!(matches!(
+        insert(&mut tree, &[1, 1, 3], Item::Separator),
+        Err(Error::InvalidPath(path)) if path == vec![1, 1, 3]
+    ));
+
+    assert!(remove(&mut tree, &[1, 1, 1]).unwrap().is_separator());
+    assert!(remove(&mut tree, &[1, 1, 1]).is_err());
+    assert_eq!(title(Some(&remove(&mut tree, &[0]).unwrap())), Some("a"));
+    assert_eq!(tree.len(), 1);
+}
+
+#[test]
+fn should_replace_items_by_path() {
+    let mut tree = mock_tree();
+
+    let replaced = replace(&mut tree, &[1, 0], Item::Separator).unwrap();
+
+    assert_eq!(title(Some(&replaced)), Some("b"));
+    assert!(get(&tree, &[1, 0]).unwrap().is_separator());
+    assert!(replace(&mut tree, &[1, 0, 0], Item::Separator).is_err());
+}
+
+#[test]
+fn should_move_items_by_path() {
+    let mut tree = mock_tree();
+
+    move_item(&mut tree, &[0], &[0, 1, 0]).unwrap();
+    assert_eq!(tree.len(), 1);
+    assert_eq!(title(get(&tree, &[0, 1, 0])), Some("a"));
+
+    move_item(&mut tree, &[0, 1, 1], &[1]).unwrap();
+    assert_eq!(title(get(&tree, &[1])), Some("c"));
+
+    // a folder can't be moved into itself, the tree is restored
+    let before = tree.clone();
+    assert!(move_item(&mut tree, &[0], &[0, 0]).is_err());
+    assert_eq!(tree, before);
+}
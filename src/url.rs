@@ -0,0 +1,261 @@
+//! Contains the canonicalization of the urls, it is used to compare the bookmarks' `href`.
+//!
+//! The same page is often bookmarked with slightly different urls : `http://x.com/`, `https://x.com`
+//! or `https://www.x.com/?utm_source=firefox-browser`. The [Rules] rewrite these urls to the same form.
+//!
+//! ```rust
+//! use bookmarkt::url;
+//!
+//! assert_eq!(
+//!     url::normalize("http://www.x.com/?utm_source=firefox-browser"),
+//!     url::normalize("https://x.com")
+//! );
+//! ```
+use ::url::Url;
+
+/// The tracking parameters stripped by default, a trailing `*` matches any suffix.
+const TRACKING_PARAMETERS: &[&str] = &[
+    "utm_*", "fbclid", "gclid", "dclid", "msclkid", "yclid", "mc_cid", "mc_eid", "_hsenc", "_hsmi",
+    "igshid", "ref_src",
+];
+
+/// Defines how the urls are canonicalized, all the rules but `strip_fragment` are enabled by default.
+///
+/// ```rust
+/// use bookmarkt::url::RulesBuilder;
+///
+/// let rules = RulesBuilder::default().strip_www(false).build().unwrap();
+///
+/// assert_eq!(rules.normalize("HTTP://www.X.com:80/a/"), "https://www.x.com/a");
+/// ```
+#[derive(Builder, Clone, Debug, PartialEq, Eq)]
+#[builder(setter(into))]
+pub struct Rules {
+    /// The `unify_scheme` rule treats the `http` scheme as `https`.
+    #[builder(default = "true")]
+    pub unify_scheme: bool,
+
+    /// The `strip_www` rule removes the `www.` prefix of the host.
+    #[builder(default = "true")]
+    pub strip_www: bool,
+
+    /// The `strip_trailing_slash` rule removes the trailing slashes of the path.
+    #[builder(default = "true")]
+    pub strip_trailing_slash: bool,
+
+    /// The `strip_default_port` rule omits the port when it is the default one of the scheme,
+    /// otherwise the port is always written.
+    #[builder(default = "true")]
+    pub strip_default_port: bool,
+
+    /// The `strip_fragment` rule removes the `#fragment` of the url. It is disabled by default, since
+    /// the fragment tells apart the pages of the single-page applications, like `#/inbox`.
+    #[builder(default = "false")]
+    pub strip_fragment: bool,
+
+    /// The `sort_query` rule sorts the query parameters by name.
+    #[builder(default = "true")]
+    pub sort_query: bool,
+
+    /// The `tracking_parameters` are the query parameters that are stripped, like `utm_source`.
+    /// A trailing `*` matches any suffix, the list is empty to keep all the parameters.
+    #[builder(default = "default_tracking_parameters()")]
+    pub tracking_parameters: Vec<String>,
+}
+
+fn default_tracking_parameters() -> Vec<String> {
+    TRACKING_PARAMETERS.iter().map(|p| p.to_string()).collect()
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            unify_scheme: true,
+            strip_www: true,
+            strip_trailing_slash: true,
+            strip_default_port: true,
+            strip_fragment: false,
+            sort_query: true,
+            tracking_parameters: default_tracking_parameters(),
+        }
+    }
+}
+
+impl Rules {
+    /// Checks if a query parameter is a tracking parameter.
+    pub fn is_tracking_parameter(&self, name: &str) -> bool {
+        self.tracking_parameters
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == pattern,
+            })
+    }
+
+    /// Canonicalizes an url, the urls that can't be parsed or have no host are only trimmed.
+    pub fn normalize(&self, href: &str) -> String {
+        let href = href.trim();
+        let url = match Url::parse(href) {
+            Ok(url) if url.has_host() => url,
+            _ => return href.to_string(),
+        };
+
+        let mut scheme = url.scheme();
+
+        if self.unify_scheme && scheme == "http" {
+            scheme = "https";
+        }
+
+        let mut normalized = format!("{}://", scheme);
+
+        if !url.username().is_empty() {
+            normalized.push_str(url.username());
+
+            if let Some(password) = url.password() {
+                normalized.push(':');
+                normalized.push_str(password);
+            }
+
+            normalized.push('@');
+        }
+
+        let host = url.host_str().unwrap_or_default();
+
+        if self.strip_www {
+            normalized.push_str(host.strip_prefix("www.").unwrap_or(host));
+        } else {
+            normalized.push_str(host);
+        }
+
+        let port = if self.strip_default_port {
+            url.port()
+        } else {
+            url.port_or_known_default()
+        };
+
+        if let Some(port) = port {
+            normalized.push_str(&format!(":{}", port));
+        }
+
+        if self.strip_trailing_slash {
+            normalized.push_str(url.path().trim_end_matches('/'));
+        } else {
+            normalized.push_str(url.path());
+        }
+
+        let mut parameters = url
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .filter(|parameter| !parameter.is_empty())
+            .filter(|parameter| {
+                let name = parameter.split('=').next().unwrap_or_default();
+                !self.is_tracking_parameter(name)
+            })
+            .collect::<Vec<_>>();
+
+        if self.sort_query {
+            parameters.sort_by_key(|parameter| parameter.split('=').next().unwrap_or_default());
+        }
+
+        if !parameters.is_empty() {
+            normalized.push('?');
+            normalized.push_str(&parameters.join("&"));
+        }
+
+        if let Some(fragment) = url.fragment().filter(|_| !self.strip_fragment) {
+            normalized.push('#');
+            normalized.push_str(fragment);
+        }
+
+        normalized
+    }
+
+    /// Checks if two urls are the same once canonicalized.
+    pub fn same(&self, href: &str, other: &str) -> bool {
+        self.normalize(href) == self.normalize(other)
+    }
+}
+
+/// Canonicalizes an url with the default [Rules].
+pub fn normalize(href: &str) -> String {
+    Rules::default().normalize(href)
+}
+
+#[test]
+fn should_normalize_scheme_host_and_port() {
+    assert_eq!(
+        normalize("HTTP://WWW.Example.com:80/"),
+        "https://example.com"
+    );
+    assert_eq!(normalize("https://example.com:443"), "https://example.com");
+    assert_eq!(
+        normalize("https://example.com:8443/a/"),
+        "https://example.com:8443/a"
+    );
+    assert_eq!(normalize("ftp://www.example.com/"), "ftp://example.com");
+}
+
+#[test]
+fn should_normalize_query_and_fragment() {
+    assert_eq!(
+        normalize("https://x.com/?utm_source=firefox-browser&b=2&a=1&fbclid=42#top"),
+        "https://x.com?a=1&b=2#top"
+    );
+    assert_eq!(normalize("https://x.com/?utm_medium=mail"), "https://x.com");
+}
+
+#[test]
+fn should_keep_hash_routes_distinct() {
+    let rules = RulesBuilder::default()
+        .strip_fragment(true)
+        .build()
+        .unwrap();
+
+    assert!(!Rules::default().same(
+        "https://app.example.com/#/inbox",
+        "https://app.example.com/#/settings"
+    ));
+    assert!(rules.same("https://x.com/#top", "https://x.com/"));
+}
+
+#[test]
+fn should_keep_unparseable_urls() {
+    assert_eq!(
+        normalize("  place:sort=8&maxResults=10 "),
+        "place:sort=8&maxResults=10"
+    );
+    assert_eq!(normalize("not an url"), "not an url");
+}
+
+#[test]
+fn should_disable_rules() {
+    let rules = RulesBuilder::default()
+        .unify_scheme(false)
+        .strip_trailing_slash(false)
+        .strip_default_port(false)
+        .strip_fragment(false)
+        .sort_query(false)
+        .tracking_parameters(vec![])
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        rules.normalize("http://www.x.com/a/?utm_source=x&b=2&a=1#top"),
+        "http://x.com:80/a/?utm_source=x&b=2&a=1#top"
+    );
+    assert!(!rules.same("http://x.com", "https://x.com"));
+    assert!(Rules::default().same("http://x.com", "https://x.com/"));
+}
+
+#[test]
+fn should_normalize_firefox_fixture_urls() {
+    use crate::Netscape;
+    use std::path::Path;
+
+    let firefox = Netscape::from_file(Path::new("./res/firefox.html")).unwrap();
+
+    for bookmark in firefox.get_bookmarks() {
+        assert!(!normalize(&bookmark.href).contains("utm_"));
+    }
+}
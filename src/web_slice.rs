@@ -0,0 +1,229 @@
+//! Contains the [WebSlice] model and its associated tests.
+use askama::Template;
+use indexmap::IndexMap;
+use kuchiki::NodeRef;
+use serde::{Deserialize, Serialize};
+
+use crate::node_ref_ext::*;
+use crate::Bookmark;
+use crate::Timestamp;
+
+/// The attributes of the `<A/>` tag that are parsed into the fields of a [WebSlice].
+const KNOWN_ATTRIBUTES: &[&str] = &[
+    "HREF",
+    "ADD_DATE",
+    "LAST_VISIT",
+    "LAST_MODIFIED",
+    "WEBSLICE",
+    "ISLIVEPREVIEW",
+    "PREVIEWSIZE",
+];
+
+/// Implements the specification of the legacy [Web Slice] item of Internet Explorer.
+///
+/// A web slice is a shortcut flagged with the `WEBSLICE="true"` attribute, it subscribes to a part of a page.
+///
+/// [Web Slice]: https://docs.microsoft.com/en-us/previous-versions/windows/desktop/cc956158(v=vs.85)
+#[derive(Serialize, Deserialize, Builder, Clone, Debug, Default, Template)]
+#[builder(setter(into))]
+#[serde(deny_unknown_fields)]
+#[template(path = "web_slice.j2", escape = "netscape")]
+pub struct WebSlice {
+    /// The `href` attribute stores the url to the page of the web slice, its fragment identifies the slice.
+    pub href: String,
+
+    /// The `title` attribute stores the web slice's title, it is the `<A/>` content.
+    #[builder(default)]
+    pub title: String,
+
+    /// The `add_date` attribute is the date when the item was created, see [Timestamp].
+    #[builder(default)]
+    pub add_date: Timestamp,
+
+    /// The `last_visit` attribute is the date of the last visit to the site of the web slice, see [Timestamp].
+    #[builder(default)]
+    pub last_visit: Timestamp,
+
+    /// The `last_modified` attribute is the date of the last modification of the item, see [Timestamp].
+    #[builder(default)]
+    pub last_modified: Timestamp,
+
+    /// The `is_live_preview` attribute indicates that the slice is previewed from the page itself,
+    /// it is the `ISLIVEPREVIEW` attribute. It is [None] when the attribute is missing, so an explicit
+    /// `ISLIVEPREVIEW="false"` is rendered back.
+    ///
+    /// It is always serialized, since it tells a web slice from the other items in JSON.
+    #[builder(default)]
+    #[serde(deserialize_with = "Option::deserialize")]
+    pub is_live_preview: Option<bool>,
+
+    /// The `preview_size` attribute stores the size of the preview window, like `"300 x 400"`.
+    /// It is the `PREVIEWSIZE` attribute.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview_size: Option<String>,
+
+    /// The `description` attribute stores the note of the web slice, it is the content of the following `<DD/>` tag.
+    /// It is plain text like [Bookmark::description], the nested tags are dropped.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+
+    /// The `extra_attributes` map stores the attributes of the `<A/>` tag that aren't modeled.
+    /// Their names are uppercased and they keep the document's order.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub extra_attributes: IndexMap<String, String>,
+}
+
+impl WebSlice {
+    /// Creates a [WebSlice] model from a parsed HTML DOM, the `<A/>` tag must be flagged with `WEBSLICE="true"`.
+    pub fn from_node(node: &NodeRef) -> Option<Self> {
+        let mut web_slice = None;
+
+        if node.is_element("DT") {
+            let a = node.children().find(|n| n.is_element("A"));
+
+            if let Some(node) = a {
+                web_slice = WebSlice::from_node(&node);
+            }
+        } else if node.is_element("A") && node.has_flag("WEBSLICE") {
+            let mut builder = WebSliceBuilder::default();
+
+            if let Some(attribute) = node.select_attribute("HREF") {
+                builder.href(attribute.value);
+            }
+
+            if let Some(attribute) = node.select_attribute("ADD_DATE") {
+                builder.add_date(attribute.value);
+            }
+
+            if let Some(attribute) = node.select_attribute("LAST_VISIT") {
+                builder.last_visit(attribute.value);
+            }
+
+            if let Some(attribute) = node.select_attribute("LAST_MODIFIED") {
+                builder.last_modified(attribute.value);
+            }
+
+            if let Some(attribute) = node.select_attribute("PREVIEWSIZE") {
+                builder.preview_size(Some(attribute.value));
+            }
+
+            if let Some(attribute) = node.select_attribute("ISLIVEPREVIEW") {
+                builder.is_live_preview(attribute.value.eq_ignore_ascii_case("true"));
+            }
+
+            builder.extra_attributes(node.select_extra_attributes(KNOWN_ATTRIBUTES));
+            builder.title(node.text_contents());
+
+            if let Some(dd) = node.select_description() {
+                builder.description(dd.description_text());
+            }
+
+            if let Ok(built) = builder.build() {
+                web_slice = Some(built);
+            }
+        }
+
+        web_slice
+    }
+
+    /// Converts the web slice to a [Bookmark] of its page.
+    ///
+    /// It is used by the formats that don't support web slices, like the browsers' JSON files.
+    pub fn to_bookmark(&self) -> Bookmark {
+        Bookmark {
+            href: self.href.clone(),
+            title: self.title.clone(),
+            add_date: self.add_date.clone(),
+            last_visit: self.last_visit.clone(),
+            last_modified: self.last_modified.clone(),
+            description: self.description.clone(),
+            extra_attributes: self.extra_attributes.clone(),
+            ..Bookmark::default()
+        }
+    }
+}
+
+impl PartialEq for WebSlice {
+    fn eq(&self, other: &Self) -> bool {
+        self.href == other.href
+            && self.add_date == other.add_date
+            && self.last_visit == other.last_visit
+            && self.last_modified == other.last_modified
+            && self.title == other.title
+            && self.is_live_preview == other.is_live_preview
+            && self.preview_size == other.preview_size
+            && self.description == other.description
+            && self.extra_attributes == other.extra_attributes
+    }
+}
+
+#[test]
+fn should_parse_netscape_web_slice() {
+    use kuchiki::parse_html;
+    use kuchiki::traits::TendrilSink;
+
+    let item = r#"<DT><A HREF="https://example.com/#weather" ADD_DATE="date" WEBSLICE="true" ISLIVEPREVIEW="true" PREVIEWSIZE="300 x 400">Weather</A>"#;
+    let a = parse_html().one(item).select_first("A").unwrap();
+
+    assert_eq!(
+        WebSlice::from_node(a.as_node()).unwrap(),
+        WebSliceBuilder::default()
+            .href("https://example.com/#weather")
+            .title("Weather")
+            .add_date("date")
+            .is_live_preview(true)
+            .preview_size(Some(String::from("300 x 400")))
+            .build()
+            .unwrap()
+    );
+}
+
+#[test]
+fn should_render_web_slice_html() {
+    let web_slice = WebSliceBuilder::default()
+        .href("url")
+        .title("name")
+        .add_date("date")
+        .preview_size(Some(String::from("300 x 400")))
+        .build()
+        .unwrap();
+
+    let rendered =
+        r#"<DT><A HREF="url" ADD_DATE="date" WEBSLICE="true" PREVIEWSIZE="300 x 400">name</A>"#;
+    assert_eq!(web_slice.render().unwrap(), rendered);
+}
+
+#[test]
+fn should_render_explicit_live_preview() {
+    use kuchiki::parse_html;
+    use kuchiki::traits::TendrilSink;
+
+    for flag in [r#" ISLIVEPREVIEW="false""#, r#" ISLIVEPREVIEW="true""#, ""] {
+        let item = format!(
+            r#"<DT><A HREF="url" ADD_DATE="date" WEBSLICE="true"{}>name</A>"#,
+            flag
+        );
+        let a = parse_html().one(item.as_str()).select_first("A").unwrap();
+        let web_slice = WebSlice::from_node(a.as_node()).unwrap();
+
+        assert_eq!(web_slice.render().unwrap(), item);
+    }
+}
+
+#[test]
+fn should_serialize_json_web_slice() {
+    let json = r#"{"href":"url","title":"name","add_date":"date","last_visit":"","last_modified":"","is_live_preview":true}"#;
+    let web_slice = WebSliceBuilder::default()
+        .href("url")
+        .title("name")
+        .add_date("date")
+        .is_live_preview(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(serde_json::to_string(&web_slice).unwrap(), json);
+    assert_eq!(serde_json::from_str::<WebSlice>(json).unwrap(), web_slice);
+}
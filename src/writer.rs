@@ -0,0 +1,220 @@
+//! Contains the [HtmlWriter] that renders a document item by item to any [std::io::Write].
+//!
+//! It renders the same HTML as [Netscape::to_html], without building the whole output nor the whole
+//! document in memory.
+//!
+//! [Netscape::to_html]: crate::Netscape::to_html
+use askama::Template;
+use indexmap::IndexMap;
+use std::io::Write;
+
+use crate::item::Item;
+use crate::Bookmark;
+use crate::Error;
+use crate::Folder;
+use crate::Timestamp;
+
+/// The header of a document, up to the opening of its root list.
+#[derive(Template)]
+#[template(path = "netscape_open.j2", escape = "netscape")]
+struct DocumentOpen<'a> {
+    title: &'a str,
+    h1: &'a str,
+}
+
+/// The header of a folder, up to the opening of its list.
+#[derive(Template)]
+#[template(path = "folder_open.j2", escape = "netscape")]
+struct FolderOpen<'a> {
+    title: &'a str,
+    folded: bool,
+    add_date: &'a Timestamp,
+    last_modified: &'a Timestamp,
+    personal_toolbar_folder: bool,
+    unfiled_bookmarks_folder: bool,
+    description: &'a str,
+    extra_attributes: &'a IndexMap<String, String>,
+}
+
+impl<'a> From<&'a Folder> for FolderOpen<'a> {
+    fn from(folder: &'a Folder) -> Self {
+        FolderOpen {
+            title: &folder.title,
+            folded: folder.folded,
+            add_date: &folder.add_date,
+            last_modified: &folder.last_modified,
+            personal_toolbar_folder: folder.personal_toolbar_folder,
+            unfiled_bookmarks_folder: folder.unfiled_bookmarks_folder,
+            description: &folder.description,
+            extra_attributes: &folder.extra_attributes,
+        }
+    }
+}
+
+/// Renders a document incrementally : the folders are opened, filled and closed in the document order.
+///
+/// The header is written by [HtmlWriter::new] and the open folders and the root list are closed by
+/// [HtmlWriter::finish]. The writer isn't buffered, a [std::io::BufWriter] should wrap the files.
+///
+/// ```rust
+/// use bookmarkt::{Bookmark, Folder, HtmlWriter, Netscape};
+///
+/// let mut writer = HtmlWriter::new(vec![], "Bookmarks", "Bookmarks").unwrap();
+/// let rust = Folder {
+///     title: "Rust".into(),
+///     ..Folder::default()
+/// };
+///
+/// writer.open_folder(&rust).unwrap();
+///
+/// for page in ["book", "reference"] {
+///     let bookmark = Bookmark {
+///         href: format!("https://doc.rust-lang.org/{}/", page),
+///         title: page.into(),
+///         ..Bookmark::default()
+///     };
+///
+///     writer.push_bookmark(&bookmark).unwrap();
+/// }
+///
+/// writer.close_folder().unwrap();
+///
+/// let html = String::from_utf8(writer.finish().unwrap()).unwrap();
+/// let netscape = Netscape::from_html(&html).unwrap();
+///
+/// assert_eq!(netscape.get_bookmarks().len(), 2);
+/// assert_eq!(netscape.to_html().unwrap(), html);
+/// ```
+pub struct HtmlWriter<W: Write> {
+    writer: W,
+    /// The number of folders that are open.
+    depth: usize,
+}
+
+impl<W: Write> HtmlWriter<W> {
+    /// Creates a writer and writes the header of the document, with its `title` and `h1`.
+    pub fn new(mut writer: W, title: &str, h1: &str) -> Result<Self, Error> {
+        writer.write_all(DocumentOpen { title, h1 }.render()?.as_bytes())?;
+
+        Ok(HtmlWriter { writer, depth: 0 })
+    }
+
+    /// Opens a folder, the next items are written in it until [HtmlWriter::close_folder].
+    ///
+    /// The `children` of the folder are ignored.
+    pub fn open_folder(&mut self, folder: &Folder) -> Result<(), Error> {
+        self.write_item(&FolderOpen::from(folder))?;
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    /// Closes the last open folder, it fails with [Error::NoOpenFolder] when no folder is open.
+    pub fn close_folder(&mut self) -> Result<(), Error> {
+        if self.depth == 0 {
+            return Err(Error::NoOpenFolder);
+        }
+
+        self.writer.write_all(b"\n</DL><p>")?;
+        self.depth -= 1;
+
+        Ok(())
+    }
+
+    /// Writes a bookmark in the last open folder.
+    pub fn push_bookmark(&mut self, bookmark: &Bookmark) -> Result<(), Error> {
+        self.write_item(bookmark)
+    }
+
+    /// Writes an item in the last open folder, a subfolder is written with all its children.
+    pub fn push(&mut self, item: &Item) -> Result<(), Error> {
+        match item {
+            Item::Subfolder(folder) => {
+                self.open_folder(folder)?;
+
+                for child in folder.children.iter() {
+                    self.push(child)?;
+                }
+
+                self.close_folder()
+            }
+            Item::Shortcut(bookmark) => self.push_bookmark(bookmark),
+            Item::Feed(feed) => self.write_item(feed),
+            Item::WebSlice(web_slice) => self.write_item(web_slice),
+            Item::Separator => Ok(self.writer.write_all(b"\n    <HR>")?),
+        }
+    }
+
+    /// Writes the indentation of an item and its rendering.
+    fn write_item<T: Template>(&mut self, template: &T) -> Result<(), Error> {
+        self.writer.write_all(b"\n    ")?;
+        self.writer.write_all(template.render()?.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Closes the open folders and the root list, and gives the inner writer back once it is flushed.
+    pub fn finish(mut self) -> Result<W, Error> {
+        while self.depth > 0 {
+            self.close_folder()?;
+        }
+
+        self.writer.write_all(b"\n</DL>")?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// Renders the children of a document with a [HtmlWriter].
+pub(crate) fn write_html<W: Write>(
+    writer: W,
+    title: &str,
+    h1: &str,
+    children: &[Item],
+) -> Result<(), Error> {
+    let mut writer = HtmlWriter::new(writer, title, h1)?;
+
+    for child in children.iter() {
+        writer.push(child)?;
+    }
+
+    writer.finish()?;
+    Ok(())
+}
+
+#[test]
+fn should_write_the_same_html() {
+    use crate::Netscape;
+    use std::path::Path;
+
+    for path in [
+        "./res/chromium.html",
+        "./res/firefox.html",
+        "./res/netscape.html",
+    ] {
+        let netscape = Netscape::from_file(Path::new(path)).unwrap();
+        let mut written = vec![];
+
+        write_html(
+            &mut written,
+            &netscape.title,
+            &netscape.h1,
+            &netscape.children,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            netscape.to_html().unwrap()
+        );
+    }
+}
+
+#[test]
+fn should_not_close_the_root_list() {
+    let mut writer = HtmlWriter::new(vec![], "", "").unwrap();
+
+    assert!(matches!(writer.close_folder(), Err(Error::NoOpenFolder)));
+    assert!(writer.finish().unwrap().ends_with(b"<DL><p>\n</DL>"));
+}